@@ -0,0 +1,413 @@
+//! Listing the available adapters, creating a [`GpuManager`] from a hand-picked one, and moving it to a
+//! different one at runtime, or to one that can present to a newly attached window.
+
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{Result, bail};
+use wgpu::{
+    Adapter, AdapterInfo, Backends, Device, DeviceDescriptor, Features, Instance, InstanceFlags,
+    Limits, Queue, RequestAdapterOptions, Surface,
+};
+
+use crate::builder::{device_descriptor, log_adapter, missing_features_message};
+use crate::{Blitter, EncoderPool, GpuManager, LOG_TARGET, Profiler};
+#[cfg(feature = "window")]
+use crate::{FrameStats, FrameTracker, WindowManager};
+#[cfg(feature = "window")]
+use winit::window::Window;
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Returns the adapters available on this system, with the index to pass to
+    /// [`GpuManager::switch_gpu`].
+    ///
+    /// This is meant for letting users pick their GPU, for example in a settings menu. The list may
+    /// include adapters that can't present to the window, which [`GpuManager::switch_gpu`] rejects.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// for (index, info) in manager.available_gpus() {
+    ///     println!("{index}: {} ({:?}, {:?})", info.name, info.device_type, info.backend);
+    /// }
+    /// ```
+    pub fn available_gpus(&self) -> Vec<(usize, AdapterInfo)> {
+        self.instance
+            .enumerate_adapters(Backends::all())
+            .iter()
+            .map(Adapter::get_info)
+            .enumerate()
+            .collect()
+    }
+
+    /// Returns the backends for which the [`Instance`] enumerates at least one adapter.
+    ///
+    /// This helps diagnosing why a backend isn't used: a backend missing here either wasn't compiled
+    /// in, was excluded through [`GpuManagerBuilder::backends`](crate::GpuManagerBuilder::backends), or
+    /// has no driver on this system. [`GpuManager::backend`] returns the one in use.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// assert!(manager.available_backends().contains(manager.backend().into()));
+    /// ```
+    pub fn available_backends(&self) -> Backends {
+        self.instance
+            .enumerate_adapters(Backends::all())
+            .iter()
+            .fold(Backends::empty(), |backends, adapter| {
+                backends | Backends::from(adapter.get_info().backend)
+            })
+    }
+
+    /// Requests a new [`Device`] and [`Queue`] from the adapter at `index` in
+    /// [`GpuManager::available_gpus`], with the same features and limits as the current device.
+    async fn request_switch(
+        &self,
+        index: usize,
+        compatible_surface: Option<&Surface<'_>>,
+    ) -> Result<(Adapter, Device, Queue)> {
+        let mut adapters = self.instance.enumerate_adapters(Backends::all());
+        if index >= adapters.len() {
+            bail!(
+                "There is no GPU at index {index}, only {} are available.",
+                adapters.len()
+            );
+        }
+        let adapter = adapters.swap_remove(index);
+        if let Some(surface) = compatible_surface
+            && !adapter.is_surface_supported(surface)
+        {
+            bail!(
+                "The GPU at index {index} ({}) can't present to the window.",
+                adapter.get_info().name
+            );
+        }
+        log_adapter(&adapter);
+
+        let mut descriptor =
+            device_descriptor(self.device.features(), self.memory_hints.clone(), &adapter)?;
+        // Subgroup sizes describe the hardware rather than a requirement, so they come from the new
+        // adapter.
+        descriptor.required_limits = Limits {
+            min_subgroup_size: descriptor.required_limits.min_subgroup_size,
+            max_subgroup_size: descriptor.required_limits.max_subgroup_size,
+            ..self.device.limits()
+        };
+        if !descriptor.required_limits.check_limits(&adapter.limits()) {
+            bail!(
+                "The GPU at index {index} ({}) doesn't support the limits of the current device.",
+                adapter.get_info().name
+            );
+        }
+
+        log::trace!(target: LOG_TARGET, "Requesting device from the new adapter...");
+        let (device, queue) = adapter.request_device(&descriptor).await?;
+        Ok((adapter, device, queue))
+    }
+
+    /// Moves the manager to `device` and `queue`, requested from `adapter`, dropping the resources tied
+    /// to the previous device, and keeping collecting its uncaptured errors.
+    fn replace_device(&mut self, adapter: Adapter, device: Device, queue: Queue) {
+        self.adapter = adapter;
+        self.device = Arc::new(device);
+        self.queue = Arc::new(queue);
+        self.blitter = Blitter::default();
+        self.standard_samplers = OnceLock::new();
+        self.encoder_pool = EncoderPool::default();
+        self.profiler = Profiler::default();
+        if let Some(error_queue) = &self.error_queue {
+            error_queue.install(&self.device);
+        }
+    }
+}
+
+impl GpuManager<()> {
+    /// Creates an [`Instance`] with every backend, and requests an [`Adapter`] from it matching
+    /// `options`, without creating a [`Device`] yet.
+    ///
+    /// This is the first half of a two-phase creation, for when the adapter's
+    /// [features](Adapter::features), [limits](Adapter::limits) or [info](Adapter::get_info) must be
+    /// inspected to tailor the [`DeviceDescriptor`]. Finish with [`GpuManager::from_adapter`]. To display
+    /// to a window, use [`GpuManager::request_adapter_for_window`] instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::Features;
+    ///
+    /// let (instance, adapter) =
+    ///     pollster::block_on(GpuManager::request_adapter_only(&Default::default())).unwrap();
+    /// // Only request timestamp queries where they are supported.
+    /// let descriptor = wgpu::DeviceDescriptor {
+    ///     required_features: adapter.features() & Features::TIMESTAMP_QUERY,
+    ///     required_limits: adapter.limits(),
+    ///     ..Default::default()
+    /// };
+    /// let manager =
+    ///     pollster::block_on(GpuManager::from_adapter(instance, adapter, &descriptor)).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) `options` has a compatible surface, which can't be from the new instance,
+    /// or 2) no adapter matches `options`.
+    pub async fn request_adapter_only(
+        options: &RequestAdapterOptions<'_, '_>,
+    ) -> Result<(Instance, Adapter)> {
+        if options.compatible_surface.is_some() {
+            bail!(
+                "The compatible surface must be created from the returned instance, use `request_adapter_for_window` instead."
+            );
+        }
+        let instance =
+            Self::create_instance(Backends::all(), InstanceFlags::from_build_config(), None);
+        log::trace!(target: LOG_TARGET, "Creating wgpu Adapter...");
+        let adapter = instance.request_adapter(options).await?;
+        Ok((instance, adapter))
+    }
+
+    /// Creates a [`GpuManager`] from an `adapter` obtained through [`GpuManager::request_adapter_only`],
+    /// requesting its [`Device`] and [`Queue`] with `descriptor`.
+    ///
+    /// The memory hints of `descriptor` are reused when switching GPUs through
+    /// [`GpuManager::switch_gpu`], along with the device's features.
+    ///
+    /// # Errors
+    /// This will error if 1) `adapter` doesn't support the features of `descriptor`, or 2) the device
+    /// request fails, for example because of unsupported limits.
+    pub async fn from_adapter(
+        instance: Instance,
+        adapter: Adapter,
+        descriptor: &DeviceDescriptor<'_>,
+    ) -> Result<Self> {
+        let missing = descriptor.required_features.difference(adapter.features());
+        if !missing.is_empty() {
+            bail!(missing_features_message(&adapter, missing));
+        }
+        log_adapter(&adapter);
+        log::trace!(target: LOG_TARGET, "Creating wgpu Device...");
+        let (device, queue) = adapter.request_device(descriptor).await?;
+        Ok(GpuManager {
+            surface_manager: (),
+            instance,
+            adapter,
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            blitter: Blitter::default(),
+            standard_samplers: OnceLock::new(),
+            encoder_pool: EncoderPool::default(),
+            profiler: Profiler::default(),
+            memory_hints: descriptor.memory_hints.clone(),
+            strict_validation: false,
+            error_queue: None,
+            error_scopes: Arc::default(),
+            dropped_features: Features::empty(),
+        })
+    }
+
+    /// Recreates the [`Device`] and [`Queue`] on the adapter at `index` in [`GpuManager::available_gpus`].
+    ///
+    /// The new device is requested with the same features, limits and memory hints as the current one.
+    ///
+    /// **Warning**: every GPU resource created from the previous device (buffers, textures, pipelines,
+    /// bind groups...) is invalid after switching, and must be recreated from [`GpuManager::device`].
+    ///
+    /// # Errors
+    /// This will error if 1) there is no adapter at `index`, 2) it doesn't support the current features
+    /// or limits, or 3) the device request fails. The manager is left untouched in that case.
+    pub async fn switch_gpu(&mut self, index: usize) -> Result<()> {
+        let (adapter, device, queue) = self.request_switch(index, None).await?;
+        self.replace_device(adapter, device, queue);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "window")]
+impl GpuManager<()> {
+    /// Same as [`GpuManager::request_adapter_only`], but also creates a [`Surface`] for `window`, and
+    /// requests an adapter that can present to it. Finish with [`GpuManager::from_adapter_with_window`].
+    ///
+    /// The compatible surface of `options` is ignored, and replaced by the created one.
+    ///
+    /// # Errors
+    /// This will error if 1) the [`Surface`] can't be created, or 2) no adapter matches `options`.
+    pub async fn request_adapter_for_window<'window>(
+        window: Arc<Window>,
+        options: &RequestAdapterOptions<'_, '_>,
+    ) -> Result<(Instance, Surface<'window>, Adapter)> {
+        let instance =
+            Self::create_instance(Backends::all(), InstanceFlags::from_build_config(), None);
+        log::trace!(target: LOG_TARGET, "Creating Surface...");
+        let surface = instance.create_surface(window)?;
+        log::trace!(target: LOG_TARGET, "Creating wgpu Adapter...");
+        let adapter = instance
+            .request_adapter(&RequestAdapterOptions {
+                power_preference: options.power_preference,
+                force_fallback_adapter: options.force_fallback_adapter,
+                compatible_surface: Some(&surface),
+            })
+            .await?;
+        Ok((instance, surface, adapter))
+    }
+
+    /// Creates a [`GpuManager`] displaying to `window` from the `surface` and `adapter` obtained through
+    /// [`GpuManager::request_adapter_for_window`], requesting the [`Device`] and [`Queue`] with
+    /// `descriptor` as in [`GpuManager::from_adapter`].
+    ///
+    /// # Errors
+    /// This will error if 1) `adapter` can't present to `surface`, 2) it doesn't support the features of
+    /// `descriptor`, 3) the device request fails, or 4) none of the supported surface formats can be
+    /// used, with a [`NoSurfaceFormats`](crate::NoSurfaceFormats) error if the surface reports none.
+    pub async fn from_adapter_with_window<'window>(
+        instance: Instance,
+        surface: Surface<'window>,
+        adapter: Adapter,
+        window: Arc<Window>,
+        descriptor: &DeviceDescriptor<'_>,
+    ) -> Result<GpuManager<WindowManager<'window>>> {
+        if !adapter.is_surface_supported(&surface) {
+            bail!(
+                "The adapter \"{}\" can't present to the window.",
+                adapter.get_info().name
+            );
+        }
+        Self::from_adapter(instance, adapter, descriptor)
+            .await?
+            .into_windowed(window, surface)
+    }
+
+    /// Turns this headless [`GpuManager`] into one displaying to `window`.
+    ///
+    /// A [`Surface`] is created for `window` from the same [`Instance`]. If the current
+    /// adapter can present to it, the [`Device`] is kept and every resource created from it stays valid.
+    /// Otherwise, the first adapter that can present to it is used, and a new [`Device`] is requested
+    /// with the same features and limits, as in [`GpuManager::switch_gpu`].
+    ///
+    /// Returns the windowed manager, along with whether the device was recreated.
+    ///
+    /// **Warning**: if the device was recreated, every GPU resource created from the previous device
+    /// (buffers, textures, pipelines, bind groups...) is invalid, and must be recreated from
+    /// [`GpuManager::device`].
+    ///
+    /// # Errors
+    /// This will error if 1) the [`Surface`] can't be created, 2) no adapter can present to it, 3) the
+    /// new adapter doesn't support the current features or limits, 4) the device request fails or 5)
+    /// none of the
+    /// supported surface formats can be used, with a [`NoSurfaceFormats`](crate::NoSurfaceFormats) error
+    /// if the surface reports none.
+    pub async fn attach_window<'window>(
+        mut self,
+        window: Arc<Window>,
+    ) -> Result<(GpuManager<WindowManager<'window>>, bool)> {
+        log::trace!(target: LOG_TARGET, "Creating Surface...");
+        let surface = self.instance.create_surface(window.clone())?;
+
+        let recreated = !self.adapter.is_surface_supported(&surface);
+        if recreated {
+            log::debug!(
+                target: LOG_TARGET,
+                "The adapter \"{}\" can't present to the window, looking for another one...",
+                self.adapter.get_info().name
+            );
+            let Some(index) = self
+                .instance
+                .enumerate_adapters(Backends::all())
+                .iter()
+                .position(|adapter| adapter.is_surface_supported(&surface))
+            else {
+                bail!("No adapter can present to the window.");
+            };
+            let (adapter, device, queue) = self.request_switch(index, Some(&surface)).await?;
+            self.replace_device(adapter, device, queue);
+        }
+
+        Ok((self.into_windowed(window, surface)?, recreated))
+    }
+
+    /// Wraps this manager around `window` and its `surface`, which the adapter must be able to present
+    /// to, and configures the surface.
+    pub(crate) fn into_windowed<'window>(
+        self,
+        window: Arc<Window>,
+        surface: Surface<'window>,
+    ) -> Result<GpuManager<WindowManager<'window>>> {
+        let config = GpuManager::<WindowManager>::create_surface_configuration(
+            &surface,
+            &self.adapter,
+            &self.device,
+            &window,
+            false,
+        )?;
+        let mut manager = GpuManager {
+            surface_manager: WindowManager {
+                monitor: window.current_monitor(),
+                window,
+                surface,
+                config,
+                show_on_first_frame: false,
+                configure_on_first_frame: false,
+                frame_tracker: None,
+                last_present: None,
+                frame_stats: FrameStats::default(),
+                needs_redraw: true,
+                render_scale: 1.0,
+                scaled_target: None,
+                preferred_format: false,
+                power_aware_present_mode: None,
+                on_battery: false,
+            },
+            instance: self.instance,
+            adapter: self.adapter,
+            device: self.device,
+            queue: self.queue,
+            blitter: self.blitter,
+            standard_samplers: self.standard_samplers,
+            encoder_pool: self.encoder_pool,
+            profiler: self.profiler,
+            memory_hints: self.memory_hints,
+            strict_validation: self.strict_validation,
+            error_queue: self.error_queue,
+            error_scopes: self.error_scopes,
+            dropped_features: self.dropped_features,
+        };
+        manager.reconfigure();
+        Ok(manager)
+    }
+}
+
+#[cfg(feature = "window")]
+impl GpuManager<WindowManager<'_>> {
+    /// Recreates the [`Device`] and [`Queue`] on the adapter at `index` in [`GpuManager::available_gpus`],
+    /// and reconfigures the [`Surface`] for it.
+    ///
+    /// The new device is requested with the same features, limits and memory hints as the current one.
+    /// The [`SurfaceConfiguration`](wgpu::SurfaceConfiguration) is kept, except for the parts the new
+    /// adapter doesn't support, which are replaced as in [`GpuManager::refresh_capabilities`].
+    ///
+    /// **Warning**: every GPU resource created from the previous device (buffers, textures, pipelines,
+    /// bind groups...) is invalid after switching, and must be recreated from [`GpuManager::device`].
+    /// Pipelines may also need to target a different surface format.
+    ///
+    /// # Errors
+    /// This will error if 1) there is no adapter at `index`, 2) it can't present to the window, 3) it
+    /// doesn't support the current features or limits, 4) the device request fails or 5) none of the
+    /// surface formats it supports can be used, with a [`NoSurfaceFormats`](crate::NoSurfaceFormats)
+    /// error if the surface reports none. The manager is left untouched in every case.
+    pub async fn switch_gpu(&mut self, index: usize) -> Result<()> {
+        let (adapter, device, queue) = self
+            .request_switch(index, Some(&self.surface_manager.surface))
+            .await?;
+        let (config, _) = self.supported_config(&adapter)?;
+        self.replace_device(adapter, device, queue);
+        if let Some(tracker) = &mut self.surface_manager.frame_tracker {
+            *tracker = FrameTracker::new(tracker.max_frames_in_flight());
+        }
+
+        self.surface_manager.config = config;
+        self.reconfigure();
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Rendering animations offscreen, one frame at a time.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use wgpu::{
+    CommandEncoder, CommandEncoderDescriptor, Extent3d, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+use crate::{GpuManager, LOG_TARGET, png};
+
+impl GpuManager<()> {
+    /// Renders `frame_count` frames of an animation to a `width` by `height` texture of the given
+    /// `format`, handing each of them to `sink` once rendered.
+    ///
+    /// For each frame, `draw` is called with the frame index, a [`CommandEncoder`] and a [`TextureView`]
+    /// of the render target. The encoder is then submitted, and the frame is read back and passed to
+    /// `sink` along with its index, as texels with tightly packed rows (see [`GpuManager::read_texture`]).
+    ///
+    /// The same render target is reused for every frame, with
+    /// [`TextureUsages::RENDER_ATTACHMENT`] and [`TextureUsages::TEXTURE_BINDING`], as well as
+    /// [`TextureUsages::STORAGE_BINDING`] when `format` supports it.
+    ///
+    /// # Examples
+    /// Rendering a fade from black to white:
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let mut frames = Vec::new();
+    /// manager
+    ///     .render_animation(
+    ///         3,
+    ///         4,
+    ///         4,
+    ///         wgpu::TextureFormat::Rgba8Unorm,
+    ///         |index, encoder, view| {
+    ///             let value = f64::from(index) / 2.0;
+    ///             encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+    ///                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+    ///                     view,
+    ///                     depth_slice: None,
+    ///                     resolve_target: None,
+    ///                     ops: wgpu::Operations {
+    ///                         load: wgpu::LoadOp::Clear(wgpu::Color {
+    ///                             r: value,
+    ///                             g: value,
+    ///                             b: value,
+    ///                             a: 1.0,
+    ///                         }),
+    ///                         store: wgpu::StoreOp::Store,
+    ///                     },
+    ///                 })],
+    ///                 ..Default::default()
+    ///             });
+    ///         },
+    ///         |index, pixels| {
+    ///             frames.push((index, pixels[0]));
+    ///             Ok(())
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(frames, [(0, 0), (1, 128), (2, 255)]);
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) `format` can't be rendered to and read back, 2) reading back a frame fails
+    /// or 3) `sink` returns an error, in which case no further frames are rendered.
+    #[track_caller]
+    pub fn render_animation(
+        &self,
+        frame_count: u32,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        mut draw: impl FnMut(u32, &mut CommandEncoder, &TextureView),
+        mut sink: impl FnMut(u32, Vec<u8>) -> Result<()>,
+    ) -> Result<()> {
+        let allowed_usages = self.format_features(format).allowed_usages;
+        let mut usage = TextureUsages::RENDER_ATTACHMENT
+            | TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_SRC;
+        if !allowed_usages.contains(usage) {
+            bail!("Texture format {format:?} can't be used as an animation render target.");
+        }
+        usage |= allowed_usages & TextureUsages::STORAGE_BINDING;
+
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("Animation render target"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        for index in 0..frame_count {
+            log::trace!(target: LOG_TARGET, "Rendering animation frame {index}/{frame_count}...");
+            let mut encoder = self
+                .device
+                .create_command_encoder(&CommandEncoderDescriptor {
+                    label: Some("Animation frame encoder"),
+                });
+            draw(index, &mut encoder, &view);
+            self.validate("Submitting the animation frame", || {
+                self.queue.submit([encoder.finish()])
+            });
+
+            let pixels = self
+                .read_texture(&texture)
+                .with_context(|| format!("Couldn't read back animation frame {index}."))?;
+            sink(index, pixels)?;
+        }
+        Ok(())
+    }
+
+    /// Renders `frame_count` frames of an animation as in [`GpuManager::render_animation`], writing
+    /// each of them to `frame_{index}.png` in `directory`.
+    ///
+    /// Frames are rendered to a [`TextureFormat::Rgba8UnormSrgb`] texture, so `draw` should output
+    /// linear colors. The PNG files are stored uncompressed.
+    ///
+    /// # Errors
+    /// This will error if 1) `directory` can't be created, 2) reading back a frame fails or 3) a file
+    /// can't be written, in which case no further frames are rendered.
+    #[track_caller]
+    pub fn render_animation_to_png(
+        &self,
+        frame_count: u32,
+        width: u32,
+        height: u32,
+        directory: impl AsRef<Path>,
+        draw: impl FnMut(u32, &mut CommandEncoder, &TextureView),
+    ) -> Result<()> {
+        let directory = directory.as_ref();
+        std::fs::create_dir_all(directory)
+            .with_context(|| format!("Couldn't create {}.", directory.display()))?;
+
+        self.render_animation(
+            frame_count,
+            width,
+            height,
+            TextureFormat::Rgba8UnormSrgb,
+            draw,
+            |index, pixels| {
+                let path = directory.join(format!("frame_{index}.png"));
+                let png = png::encode_rgba8(width, height, &pixels)?;
+                std::fs::write(&path, png)
+                    .with_context(|| format!("Couldn't write {}.", path.display()))
+            },
+        )
+    }
+}
@@ -0,0 +1,282 @@
+//! Helpers to create bind group layouts and bind groups.
+
+use std::num::NonZeroU32;
+
+use anyhow::{Result, bail};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Device, Features, SamplerBindingType,
+    ShaderStages, StorageTextureAccess, TextureFormat, TextureFormatFeatureFlags,
+    TextureSampleType, TextureUsages, TextureViewDimension,
+};
+
+use crate::GpuManager;
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Returns a [`BindingArrayLayoutBuilder`] to create a [`BindGroupLayout`] made of binding arrays.
+    ///
+    /// Requires the features requested by [`GpuManagerBuilder::bindless`](crate::GpuManagerBuilder::bindless).
+    pub fn binding_array_layout(&self) -> BindingArrayLayoutBuilder<'_> {
+        BindingArrayLayoutBuilder {
+            device: &self.device,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates a [`BindGroup`] for `layout`, binding each of `resources` to its index in the slice.
+    ///
+    /// This covers the common case of sequential bindings starting at 0. For anything else, use
+    /// [`Device::create_bind_group`] directly.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let (_, view) = manager
+    ///     .create_output_texture(64, 64, wgpu::TextureFormat::Rgba8Unorm)
+    ///     .unwrap();
+    /// let output = manager
+    ///     .storage_texture_entry(
+    ///         0,
+    ///         wgpu::ShaderStages::COMPUTE,
+    ///         wgpu::TextureFormat::Rgba8Unorm,
+    ///         wgpu::StorageTextureAccess::WriteOnly,
+    ///     )
+    ///     .unwrap();
+    /// let layout = manager
+    ///     .device()
+    ///     .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+    ///         label: None,
+    ///         entries: &[output],
+    ///     });
+    /// let bind_group = manager.bind_group(&layout, &[wgpu::BindingResource::TextureView(&view)]);
+    /// ```
+    pub fn bind_group(&self, layout: &BindGroupLayout, resources: &[BindingResource]) -> BindGroup {
+        let entries: Vec<_> = resources
+            .iter()
+            .zip(0..)
+            .map(|(resource, binding)| BindGroupEntry {
+                binding,
+                resource: resource.clone(),
+            })
+            .collect();
+        self.device.create_bind_group(&BindGroupDescriptor {
+            label: None,
+            layout,
+            entries: &entries,
+        })
+    }
+
+    /// Returns a [`BindGroupLayoutEntry`] for a 2D storage texture of the given `format`, accessed as
+    /// `access`, at `binding`.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let output = manager
+    ///     .storage_texture_entry(
+    ///         0,
+    ///         wgpu::ShaderStages::COMPUTE,
+    ///         wgpu::TextureFormat::Rgba8Unorm,
+    ///         wgpu::StorageTextureAccess::WriteOnly,
+    ///     )
+    ///     .unwrap();
+    /// let layout = manager
+    ///     .device()
+    ///     .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+    ///         label: Some("Output layout"),
+    ///         entries: &[output],
+    ///     });
+    ///
+    /// // sRGB formats can't be used as storage textures.
+    /// assert!(manager
+    ///     .storage_texture_entry(
+    ///         0,
+    ///         wgpu::ShaderStages::COMPUTE,
+    ///         wgpu::TextureFormat::Rgba8UnormSrgb,
+    ///         wgpu::StorageTextureAccess::WriteOnly,
+    ///     )
+    ///     .is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if `format` can't be used as a storage texture with `access` on the [`Device`].
+    pub fn storage_texture_entry(
+        &self,
+        binding: u32,
+        visibility: ShaderStages,
+        format: TextureFormat,
+        access: StorageTextureAccess,
+    ) -> Result<BindGroupLayoutEntry> {
+        let format_features = self.format_features(format);
+        if !format_features
+            .allowed_usages
+            .contains(TextureUsages::STORAGE_BINDING)
+        {
+            bail!("Texture format {format:?} can't be used as a storage texture.");
+        }
+        let required_flag = match access {
+            StorageTextureAccess::ReadOnly => TextureFormatFeatureFlags::STORAGE_READ_ONLY,
+            StorageTextureAccess::WriteOnly => TextureFormatFeatureFlags::STORAGE_WRITE_ONLY,
+            StorageTextureAccess::ReadWrite => TextureFormatFeatureFlags::STORAGE_READ_WRITE,
+            StorageTextureAccess::Atomic => TextureFormatFeatureFlags::STORAGE_ATOMIC,
+        };
+        if !format_features.flags.contains(required_flag) {
+            bail!("Texture format {format:?} doesn't support {access:?} storage access.");
+        }
+
+        Ok(BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: BindingType::StorageTexture {
+                access,
+                format,
+                view_dimension: TextureViewDimension::D2,
+            },
+            count: None,
+        })
+    }
+}
+
+/// Builds a [`BindGroupLayout`] made of arrays of textures and samplers, for bindless rendering.
+///
+/// The size of each array is validated against the [`Device`]'s
+/// [`max_binding_array_elements_per_shader_stage`](wgpu::Limits::max_binding_array_elements_per_shader_stage)
+/// and [`max_binding_array_sampler_elements_per_shader_stage`](wgpu::Limits::max_binding_array_sampler_elements_per_shader_stage)
+/// limits. When no size is given, the array is made as big as the limit allows.
+///
+/// Obtained through [`GpuManager::binding_array_layout`].
+///
+/// # Examples
+/// ```
+/// use gpu_manager::GpuManager;
+///
+/// // Binding arrays weren't requested, so building the layout fails.
+/// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+/// let layout = manager
+///     .binding_array_layout()
+///     .textures(
+///         0,
+///         wgpu::ShaderStages::FRAGMENT,
+///         wgpu::TextureSampleType::Float { filterable: true },
+///         None,
+///     )
+///     .samplers(1, wgpu::ShaderStages::FRAGMENT, wgpu::SamplerBindingType::Filtering, Some(1))
+///     .build(Some("Bindless layout"));
+/// assert!(layout.is_err());
+/// ```
+#[derive(Debug)]
+pub struct BindingArrayLayoutBuilder<'manager> {
+    device: &'manager Device,
+    entries: Vec<(BindGroupLayoutEntry, Option<u32>)>,
+}
+
+impl BindingArrayLayoutBuilder<'_> {
+    /// Adds an array of `count` 2D textures at `binding`.
+    ///
+    /// If `count` is [`None`], the biggest size allowed by the limits is used.
+    pub fn textures(
+        mut self,
+        binding: u32,
+        visibility: ShaderStages,
+        sample_type: TextureSampleType,
+        count: Option<u32>,
+    ) -> Self {
+        let ty = BindingType::Texture {
+            sample_type,
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        };
+        self.entries.push((
+            BindGroupLayoutEntry {
+                binding,
+                visibility,
+                ty,
+                count: None,
+            },
+            count,
+        ));
+        self
+    }
+
+    /// Adds an array of `count` samplers at `binding`.
+    ///
+    /// If `count` is [`None`], the biggest size allowed by the limits is used.
+    pub fn samplers(
+        mut self,
+        binding: u32,
+        visibility: ShaderStages,
+        sampler_type: SamplerBindingType,
+        count: Option<u32>,
+    ) -> Self {
+        self.entries.push((
+            BindGroupLayoutEntry {
+                binding,
+                visibility,
+                ty: BindingType::Sampler(sampler_type),
+                count: None,
+            },
+            count,
+        ));
+        self
+    }
+
+    /// Creates the [`BindGroupLayout`].
+    ///
+    /// # Errors
+    /// This will error if 1) the [`Device`] doesn't support binding arrays, or 2) an array is empty or
+    /// the arrays use more elements than the limits allow.
+    pub fn build(self, label: Option<&str>) -> Result<BindGroupLayout> {
+        if !self
+            .device
+            .features()
+            .contains(Features::TEXTURE_BINDING_ARRAY)
+        {
+            bail!(
+                "Binding arrays aren't enabled on the device. Request them with `GpuManagerBuilder::bindless`."
+            );
+        }
+
+        let limits = self.device.limits();
+        let mut texture_elements = 0;
+        let mut sampler_elements = 0;
+        let mut entries = Vec::with_capacity(self.entries.len());
+        for (mut entry, count) in self.entries {
+            let (max, used) = match entry.ty {
+                BindingType::Sampler(_) => (
+                    limits.max_binding_array_sampler_elements_per_shader_stage,
+                    &mut sampler_elements,
+                ),
+                _ => (
+                    limits.max_binding_array_elements_per_shader_stage,
+                    &mut texture_elements,
+                ),
+            };
+            let count = count.unwrap_or(max.saturating_sub(*used));
+            *used += count;
+            if *used > max {
+                bail!(
+                    "Binding array at binding {} needs {count} elements, which makes {} elements per shader stage, \
+                    but the device only allows {max}.",
+                    entry.binding,
+                    *used
+                );
+            }
+            let Some(count) = NonZeroU32::new(count) else {
+                bail!("Binding array at binding {} is empty.", entry.binding);
+            };
+            entry.count = Some(count);
+            entries.push(entry);
+        }
+
+        Ok(self
+            .device
+            .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label,
+                entries: &entries,
+            }))
+    }
+}
@@ -0,0 +1,70 @@
+//! Blend states for dual-source blending, where the fragment shader outputs the blend factors along
+//! with the color.
+
+use anyhow::{Result, bail};
+use wgpu::{BlendComponent, BlendFactor, BlendOperation, BlendState, Features};
+
+use crate::GpuManager;
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Returns a [`BlendState`] for dual-source blending, where each channel of the color blends with
+    /// its own coverage, as used for subpixel text rendering or component-alpha compositing.
+    ///
+    /// The fragment shader outputs a premultiplied color as its first source and the per channel
+    /// coverage as its second, both at location 0:
+    /// ```wgsl
+    /// enable dual_source_blending;
+    ///
+    /// struct FragmentOutput {
+    ///     @location(0) @blend_src(0) color: vec4f,
+    ///     @location(0) @blend_src(1) coverage: vec4f,
+    /// }
+    /// ```
+    /// The color target then holds `color + target * (1 - coverage)`, with the alpha channel using the
+    /// coverage's alpha. A pipeline using this must have a single color target.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::Features;
+    ///
+    /// let manager = pollster::block_on(
+    ///     GpuManager::builder()
+    ///         .desired_features(Features::DUAL_SOURCE_BLENDING)
+    ///         .build(),
+    /// )
+    /// .unwrap();
+    /// let supported = manager
+    ///     .device()
+    ///     .features()
+    ///     .contains(Features::DUAL_SOURCE_BLENDING);
+    /// assert_eq!(manager.dual_source_blend_state().is_ok(), supported);
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if [`Features::DUAL_SOURCE_BLENDING`] wasn't requested through
+    /// [`GpuManagerBuilder::dual_source_blending`](crate::GpuManagerBuilder::dual_source_blending).
+    pub fn dual_source_blend_state(&self) -> Result<BlendState> {
+        if !self
+            .device
+            .features()
+            .contains(Features::DUAL_SOURCE_BLENDING)
+        {
+            bail!(
+                "Dual-source blending requires the DUAL_SOURCE_BLENDING feature, enable it with `GpuManagerBuilder::dual_source_blending`."
+            );
+        }
+        Ok(BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::OneMinusSrc1,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::OneMinusSrc1Alpha,
+                operation: BlendOperation::Add,
+            },
+        })
+    }
+}
@@ -0,0 +1,274 @@
+//! Copying textures with a fullscreen triangle, converting between formats along the way.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Result, bail};
+use wgpu::{
+    AddressMode, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingResource, BindingType, Color, ColorTargetState, ColorWrites,
+    CommandEncoder, Device, FilterMode, FragmentState, LoadOp, MultisampleState, Operations,
+    PipelineCompilationOptions, PipelineLayoutDescriptor, PrimitiveState,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
+    Sampler, SamplerBindingType, SamplerDescriptor, ShaderModule, ShaderModuleDescriptor,
+    ShaderSource, ShaderStages, StoreOp, TextureFormat, TextureSampleType, TextureView,
+    TextureViewDimension, VertexState,
+};
+
+use crate::{GpuManager, LOG_TARGET};
+
+const BLIT_SHADER: &str = r"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // A single triangle covering the whole target, with uvs going from 0 to 1 across it.
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(source, source_sampler, in.uv);
+}
+";
+
+/// The lazily created state needed by [`GpuManager::blit`].
+#[derive(Debug, Default)]
+pub(crate) struct Blitter {
+    resources: OnceLock<BlitResources>,
+    /// Pipelines keyed by destination format and whether the source is filterable.
+    pipelines: Mutex<HashMap<(TextureFormat, bool), RenderPipeline>>,
+}
+
+#[derive(Debug)]
+struct BlitResources {
+    shader: ShaderModule,
+    /// Layout and sampler for filterable sources, which are sampled linearly.
+    filtering: (BindGroupLayout, Sampler),
+    /// Layout and sampler for non filterable sources, which are sampled with the nearest texel.
+    non_filtering: (BindGroupLayout, Sampler),
+}
+
+impl BlitResources {
+    fn new(device: &Device) -> Self {
+        log::trace!(target: LOG_TARGET, "Creating blit resources...");
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Blit shader"),
+            source: ShaderSource::Wgsl(BLIT_SHADER.into()),
+        });
+        let layout_and_sampler = |filterable: bool| {
+            let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("Blit bind group layout"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable },
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Sampler(if filterable {
+                            SamplerBindingType::Filtering
+                        } else {
+                            SamplerBindingType::NonFiltering
+                        }),
+                        count: None,
+                    },
+                ],
+            });
+            let filter = if filterable {
+                FilterMode::Linear
+            } else {
+                FilterMode::Nearest
+            };
+            let sampler = device.create_sampler(&SamplerDescriptor {
+                label: Some("Blit sampler"),
+                address_mode_u: AddressMode::ClampToEdge,
+                address_mode_v: AddressMode::ClampToEdge,
+                mag_filter: filter,
+                min_filter: filter,
+                ..Default::default()
+            });
+            (layout, sampler)
+        };
+
+        Self {
+            shader,
+            filtering: layout_and_sampler(true),
+            non_filtering: layout_and_sampler(false),
+        }
+    }
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Copies `src_view` over the whole of `dst_view` by drawing a fullscreen triangle, recording the
+    /// pass into `encoder`.
+    ///
+    /// The source is stretched to the size of the destination, sampled linearly when its format is
+    /// filterable and with the nearest texel otherwise. Since the copy goes through a shader, the formats
+    /// don't need to match: for example, a linear `Rgba16Float` texture can be blitted to an sRGB surface.
+    ///
+    /// The source texture needs [`TextureUsages::TEXTURE_BINDING`](wgpu::TextureUsages::TEXTURE_BINDING)
+    /// and the destination [`TextureUsages::RENDER_ATTACHMENT`](wgpu::TextureUsages::RENDER_ATTACHMENT).
+    /// The destination is assumed to use the format of its texture; views with a different view format
+    /// aren't supported.
+    ///
+    /// The pipeline is created the first time each destination format is used, and reused afterwards.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let (source, source_view) = manager
+    ///     .create_output_texture(64, 64, wgpu::TextureFormat::Rgba8Unorm)
+    ///     .unwrap();
+    /// let destination = manager.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size: wgpu::Extent3d {
+    ///         width: 128,
+    ///         height: 128,
+    ///         depth_or_array_layers: 1,
+    ///     },
+    ///     mip_level_count: 1,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: wgpu::TextureFormat::Bgra8UnormSrgb,
+    ///     usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+    ///     view_formats: &[],
+    /// });
+    /// let destination_view = destination.create_view(&Default::default());
+    ///
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// manager.blit(&mut encoder, &source_view, &destination_view).unwrap();
+    /// manager.queue().submit([encoder.finish()]);
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if the source or destination formats aren't color formats that can be sampled as
+    /// floats, such as integer or depth formats.
+    pub fn blit(
+        &self,
+        encoder: &mut CommandEncoder,
+        src_view: &TextureView,
+        dst_view: &TextureView,
+    ) -> Result<()> {
+        let src_format = src_view.texture().format();
+        let dst_format = dst_view.texture().format();
+        let filterable = match src_format.sample_type(None, Some(self.device.features())) {
+            Some(TextureSampleType::Float { filterable }) => filterable,
+            _ => bail!("Can't blit from {src_format:?}, only float color formats are supported."),
+        };
+        if !matches!(
+            dst_format.sample_type(None, None),
+            Some(TextureSampleType::Float { .. })
+        ) {
+            bail!("Can't blit to {dst_format:?}, only float color formats are supported.");
+        }
+
+        let resources = self
+            .blitter
+            .resources
+            .get_or_init(|| BlitResources::new(&self.device));
+        let (layout, sampler) = if filterable {
+            &resources.filtering
+        } else {
+            &resources.non_filtering
+        };
+
+        let mut pipelines = self
+            .blitter
+            .pipelines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let pipeline = pipelines
+            .entry((dst_format, filterable))
+            .or_insert_with(|| {
+                log::trace!(target: LOG_TARGET, "Creating blit pipeline for {dst_format:?}...");
+                let pipeline_layout =
+                    self.device
+                        .create_pipeline_layout(&PipelineLayoutDescriptor {
+                            label: Some("Blit pipeline layout"),
+                            bind_group_layouts: &[layout],
+                            push_constant_ranges: &[],
+                        });
+                self.device
+                    .create_render_pipeline(&RenderPipelineDescriptor {
+                        label: Some("Blit pipeline"),
+                        layout: Some(&pipeline_layout),
+                        vertex: VertexState {
+                            module: &resources.shader,
+                            entry_point: Some("vs_main"),
+                            compilation_options: PipelineCompilationOptions::default(),
+                            buffers: &[],
+                        },
+                        primitive: PrimitiveState::default(),
+                        depth_stencil: None,
+                        multisample: MultisampleState::default(),
+                        fragment: Some(FragmentState {
+                            module: &resources.shader,
+                            entry_point: Some("fs_main"),
+                            compilation_options: PipelineCompilationOptions::default(),
+                            targets: &[Some(ColorTargetState {
+                                format: dst_format,
+                                blend: None,
+                                write_mask: ColorWrites::ALL,
+                            })],
+                        }),
+                        multiview: None,
+                        cache: None,
+                    })
+            });
+
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Blit bind group"),
+            layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(src_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+
+        let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Blit pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: dst_view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::TRANSPARENT),
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Buffers the CPU writes to directly by mapping them, without a staging copy.
+
+use anyhow::{Context, Result, bail};
+use wgpu::{
+    Buffer, BufferAddress, BufferDescriptor, BufferUsages, DeviceType, Features, MapMode, PollType,
+};
+
+use crate::{GpuManager, LOG_TARGET};
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Creates a buffer of `size` bytes with the given `usage`, that can also be mapped for writing
+    /// through [`GpuManager::write_mapped`].
+    ///
+    /// Without [`Features::MAPPABLE_PRIMARY_BUFFERS`], requested through
+    /// [`GpuManagerBuilder::mappable_primary_buffers`](crate::GpuManagerBuilder::mappable_primary_buffers),
+    /// mappable buffers can only be copy sources, which is how staging buffers work. With it, they can
+    /// have any usage, such as [`BufferUsages::UNIFORM`], and be written to without a copy. A warning is
+    /// logged when doing so on a discrete GPU, where reading mapped memory from shaders is usually slow.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::BufferUsages;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let staging = manager
+    ///     .create_mappable_buffer(Some("Staging"), 16, BufferUsages::COPY_SRC)
+    ///     .unwrap();
+    /// manager.write_mapped(&staging, 0, &[1; 16]).unwrap();
+    ///
+    /// // Uniform buffers can only be mapped with `GpuManagerBuilder::mappable_primary_buffers`.
+    /// assert!(manager
+    ///     .create_mappable_buffer(Some("Uniforms"), 16, BufferUsages::UNIFORM)
+    ///     .is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if `usage` has other usages than [`BufferUsages::COPY_SRC`] while
+    /// [`Features::MAPPABLE_PRIMARY_BUFFERS`] wasn't requested.
+    pub fn create_mappable_buffer(
+        &self,
+        label: Option<&str>,
+        size: BufferAddress,
+        usage: BufferUsages,
+    ) -> Result<Buffer> {
+        let primary = usage.difference(BufferUsages::MAP_WRITE | BufferUsages::COPY_SRC);
+        if !primary.is_empty() {
+            if !self
+                .device
+                .features()
+                .contains(Features::MAPPABLE_PRIMARY_BUFFERS)
+            {
+                bail!(
+                    "Mappable buffers with the {primary:?} usages require the MAPPABLE_PRIMARY_BUFFERS feature, enable it with `GpuManagerBuilder::mappable_primary_buffers`."
+                );
+            }
+            if self.adapter.get_info().device_type == DeviceType::DiscreteGpu {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "Creating a mappable {primary:?} buffer on a discrete GPU, shaders will likely access it slowly."
+                );
+            }
+        }
+        Ok(self.device.create_buffer(&BufferDescriptor {
+            label,
+            size,
+            usage: usage | BufferUsages::MAP_WRITE,
+            mapped_at_creation: false,
+        }))
+    }
+
+    /// Maps `data.len()` bytes of `buffer` from `offset`, copies `data` into them and unmaps the buffer.
+    ///
+    /// The buffer needs [`BufferUsages::MAP_WRITE`], as given by [`GpuManager::create_mappable_buffer`].
+    /// This blocks until the GPU has finished the work using the buffer, so it is best done before
+    /// submitting it for the frame.
+    ///
+    /// # Errors
+    /// This will error if 1) `buffer` can't be mapped for writing, 2) `offset` isn't a multiple of
+    /// [`MAP_ALIGNMENT`](wgpu::MAP_ALIGNMENT) (8) or the length of `data` of
+    /// [`COPY_BUFFER_ALIGNMENT`](wgpu::COPY_BUFFER_ALIGNMENT) (4), or 3) the written range doesn't fit in
+    /// `buffer`.
+    pub fn write_mapped(&self, buffer: &Buffer, offset: BufferAddress, data: &[u8]) -> Result<()> {
+        if !buffer.usage().contains(BufferUsages::MAP_WRITE) {
+            bail!("Only buffers with the MAP_WRITE usage can be written to through mapping.");
+        }
+        let size = data.len() as BufferAddress;
+        if !offset.is_multiple_of(wgpu::MAP_ALIGNMENT)
+            || !size.is_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+        {
+            bail!(
+                "The offset ({offset}) must be a multiple of {} and the data length ({size}) of {}.",
+                wgpu::MAP_ALIGNMENT,
+                wgpu::COPY_BUFFER_ALIGNMENT
+            );
+        }
+        let Some(end) = offset.checked_add(size).filter(|end| *end <= buffer.size()) else {
+            bail!(
+                "Writing {size} bytes at offset {offset} overflows the buffer of {} bytes.",
+                buffer.size()
+            );
+        };
+        if size == 0 {
+            return Ok(());
+        }
+
+        let slice = buffer.slice(offset..end);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(MapMode::Write, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(PollType::Wait)?;
+        receiver.recv().context("The buffer was never mapped.")??;
+        slice.get_mapped_range_mut().copy_from_slice(data);
+        buffer.unmap();
+        Ok(())
+    }
+}
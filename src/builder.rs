@@ -0,0 +1,1153 @@
+//! Configurable creation of a [`GpuManager`].
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use wgpu::{
+    Adapter, Backends, DeviceDescriptor, DeviceType, Features, Instance, InstanceFlags, Limits,
+    MemoryHints, RequestAdapterOptions, Surface,
+};
+#[cfg(feature = "window")]
+use wgpu::{TextureFormat, TextureUsages};
+#[cfg(feature = "window")]
+use winit::{
+    dpi::{PhysicalSize, Size},
+    window::{Theme, Window, WindowAttributes},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::executor::sleep;
+#[cfg(feature = "window")]
+use crate::instrument::step;
+use crate::instrument::{CreationTimings, step_async};
+use crate::profiler::TIMESTAMP_FEATURES;
+use crate::{Blitter, EncoderPool, GpuManager, LOG_TARGET, Preset, Profiler};
+#[cfg(feature = "window")]
+use crate::{ColorSpace, FrameStats, FrameTracker, WindowManager, WindowMode};
+
+/// Configures how a [`GpuManager`] is created.
+///
+/// Obtain one through [`GpuManager::builder`], set the desired options and finish with
+/// [`GpuManagerBuilder::build`] or [`GpuManagerBuilder::build_with_window`].
+///
+/// # Examples
+/// ```
+/// use gpu_manager::GpuManager;
+///
+/// let manager = pollster::block_on(GpuManager::builder().build()).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GpuManagerBuilder {
+    required_features: Features,
+    /// The features requested only if the adapter supports them.
+    desired_features: Features,
+    device_type_preference: Vec<DeviceType>,
+    backend_order: Vec<Backends>,
+    backends_from_env: bool,
+    adapter_retries: u32,
+    instance_flags: InstanceFlags,
+    memory_hints: MemoryHints,
+    #[cfg(feature = "window")]
+    preset: Option<Preset>,
+    #[cfg(feature = "window")]
+    window_attributes: WindowAttributes,
+    #[cfg(feature = "window")]
+    defer_visible: bool,
+    #[cfg(feature = "window")]
+    lazy_configure: bool,
+    #[cfg(feature = "window")]
+    max_frames_in_flight: Option<usize>,
+    #[cfg(feature = "window")]
+    frame_stats_window: usize,
+    #[cfg(feature = "window")]
+    auto_srgb_views: bool,
+    #[cfg(feature = "window")]
+    surface_usage: Option<TextureUsages>,
+    #[cfg(feature = "window")]
+    preferred_surface_format: bool,
+    #[cfg(feature = "window")]
+    color_space: ColorSpace,
+    /// The surface format to use instead of choosing one.
+    #[cfg(feature = "window")]
+    match_format: Option<TextureFormat>,
+    #[cfg(feature = "window")]
+    window_mode: WindowMode,
+}
+
+impl Default for GpuManagerBuilder {
+    fn default() -> Self {
+        Self {
+            required_features: Features::empty(),
+            desired_features: Features::empty(),
+            device_type_preference: Vec::new(),
+            backend_order: Vec::new(),
+            backends_from_env: false,
+            adapter_retries: DEFAULT_ADAPTER_RETRIES,
+            instance_flags: InstanceFlags::from_build_config(),
+            memory_hints: MemoryHints::default(),
+            #[cfg(feature = "window")]
+            preset: None,
+            #[cfg(feature = "window")]
+            window_attributes: WindowAttributes::default()
+                .with_resizable(false)
+                .with_title("Ray tracer"),
+            #[cfg(feature = "window")]
+            defer_visible: false,
+            #[cfg(feature = "window")]
+            lazy_configure: false,
+            #[cfg(feature = "window")]
+            max_frames_in_flight: None,
+            #[cfg(feature = "window")]
+            frame_stats_window: FrameStats::default().window(),
+            #[cfg(feature = "window")]
+            auto_srgb_views: false,
+            #[cfg(feature = "window")]
+            surface_usage: None,
+            #[cfg(feature = "window")]
+            preferred_surface_format: false,
+            #[cfg(feature = "window")]
+            color_space: ColorSpace::default(),
+            #[cfg(feature = "window")]
+            match_format: None,
+            #[cfg(feature = "window")]
+            window_mode: WindowMode::default(),
+        }
+    }
+}
+
+impl GpuManagerBuilder {
+    /// Creates a builder with the default options.
+    ///
+    /// Unlike [`GpuManager::with_window`], the window is *not* maximized by default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the order in which [`DeviceType`]s are preferred when choosing the adapter, such as
+    /// `[DeviceType::DiscreteGpu, DeviceType::IntegratedGpu]`.
+    ///
+    /// Every adapter is enumerated and, when creating a window, those that can't present to its surface
+    /// are discarded. The first remaining adapter of the most preferred type is chosen, with types missing
+    /// from `preference` ranked last. If no adapter is left, wgpu's default adapter selection is used.
+    ///
+    /// By default, wgpu's default adapter selection is always used.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::DeviceType;
+    ///
+    /// let manager = pollster::block_on(
+    ///     GpuManager::builder()
+    ///         .device_type_preference([DeviceType::DiscreteGpu, DeviceType::IntegratedGpu])
+    ///         .build(),
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn device_type_preference(
+        mut self,
+        preference: impl IntoIterator<Item = DeviceType>,
+    ) -> Self {
+        self.device_type_preference = preference.into_iter().collect();
+        self
+    }
+
+    /// Sets the backends to try, in order, such as `[Backends::VULKAN, Backends::GL]`.
+    ///
+    /// For each entry, an [`Instance`] limited to those backends is created, and an adapter is requested
+    /// from it (and, when creating a window, a [`Surface`] for it). The first entry yielding an adapter
+    /// is used, and the backend that succeeded can be queried with [`GpuManager::backend`].
+    ///
+    /// By default, a single [`Instance`] with [`Backends::all`] is created, letting wgpu choose.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::Backends;
+    ///
+    /// let manager = pollster::block_on(
+    ///     GpuManager::builder()
+    ///         .backends([Backends::VULKAN, Backends::METAL, Backends::DX12, Backends::GL])
+    ///         .build(),
+    /// )
+    /// .unwrap();
+    /// println!("Running on {}", manager.backend());
+    /// ```
+    pub fn backends(mut self, order: impl IntoIterator<Item = Backends>) -> Self {
+        self.backend_order = order.into_iter().collect();
+        self
+    }
+
+    /// Sets whether the `WGPU_BACKEND` environment variable, a comma separated list of backend names
+    /// such as `vulkan,gl`, is tried first. Defaults to false.
+    ///
+    /// This lets users force a backend without recompiling, for example to debug backend specific
+    /// issues. Backends that weren't compiled in are ignored with a warning. If the variable names no
+    /// usable backend, or yields no adapter, a warning is logged and the order given to
+    /// [`GpuManagerBuilder::backends`] is used instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// // Run with `WGPU_BACKEND=gl` to force OpenGL.
+    /// let manager = pollster::block_on(GpuManager::builder().backends_from_env(true).build()).unwrap();
+    /// println!("Running on {}", manager.backend());
+    /// ```
+    pub fn backends_from_env(mut self, enabled: bool) -> Self {
+        self.backends_from_env = enabled;
+        self
+    }
+
+    /// Sets how many times requesting an adapter is retried when none is found. Defaults to 2.
+    ///
+    /// Some drivers initialize lazily, and report no adapter right after a cold start. Retries are
+    /// spaced by a delay starting at 50ms and doubling each time, timed on a helper thread so that the
+    /// executor polling the build isn't blocked. On the web, where threads aren't available, retries
+    /// happen right away. With [`GpuManagerBuilder::backends`], each entry is retried before moving to
+    /// the next.
+    pub fn adapter_retries(mut self, retries: u32) -> Self {
+        self.adapter_retries = retries;
+        self
+    }
+
+    /// Sets the [`InstanceFlags`] the [`Instance`] is created with.
+    ///
+    /// Defaults to [`InstanceFlags::from_build_config`]: in debug builds, validation and debug labels
+    /// are enabled ([`InstanceFlags::debugging`]), so API misuse is reported during development. In
+    /// release builds, only the cheap [`InstanceFlags::VALIDATION_INDIRECT_CALL`] is kept. The
+    /// `WGPU_*` environment variables aren't read, use [`InstanceFlags::with_env`] for that.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::InstanceFlags;
+    ///
+    /// let builder = GpuManager::builder().instance_flags(InstanceFlags::debugging().with_env());
+    /// let manager = pollster::block_on(builder.build()).unwrap();
+    /// ```
+    pub fn instance_flags(mut self, flags: InstanceFlags) -> Self {
+        self.instance_flags = flags;
+        self
+    }
+
+    /// Sets the [`MemoryHints`] the [`Device`](wgpu::Device) is requested with. Defaults to
+    /// [`MemoryHints::Performance`].
+    pub fn memory_hints(mut self, memory_hints: MemoryHints) -> Self {
+        self.memory_hints = memory_hints;
+        self
+    }
+
+    /// Applies a [`Preset`], bundling the memory hints and, for windowed managers, the present mode,
+    /// frame latency and render scale. See [`Preset`] for what each one sets.
+    ///
+    /// Options set afterwards, such as [`GpuManagerBuilder::memory_hints`], override the preset.
+    pub fn preset(mut self, preset: Preset) -> Self {
+        self.memory_hints = preset.memory_hints();
+        #[cfg(feature = "window")]
+        {
+            self.preset = Some(preset);
+        }
+        self
+    }
+
+    /// Requests the given [`Features`], in addition to the ones already requested.
+    ///
+    /// Creation will fail if the adapter doesn't support every requested feature. For the most common
+    /// features, the named toggles (such as [`GpuManagerBuilder::float32_filterable`]) can be used
+    /// instead.
+    pub fn features(mut self, features: Features) -> Self {
+        self.required_features.insert(features);
+        self
+    }
+
+    /// Requests the given [`Features`] if the adapter supports them, in addition to the ones already
+    /// desired.
+    ///
+    /// Unlike [`GpuManagerBuilder::features`], creation doesn't fail when some of them aren't supported:
+    /// only the supported subset is requested, and the others can be queried afterwards through
+    /// [`GpuManager::dropped_features`]. This lets applications degrade gracefully, checking
+    /// [`Device::features`](wgpu::Device::features) before using an optional feature.
+    pub fn desired_features(mut self, features: Features) -> Self {
+        self.desired_features.insert(features);
+        self
+    }
+
+    /// Requests support for filtering `R32Float`, `Rg32Float` and `Rgba32Float` textures
+    /// ([`Features::FLOAT32_FILTERABLE`]).
+    pub fn float32_filterable(mut self, enabled: bool) -> Self {
+        self.required_features
+            .set(Features::FLOAT32_FILTERABLE, enabled);
+        self
+    }
+
+    /// Requests support for disabling depth clipping through
+    /// [`PrimitiveState::unclipped_depth`](wgpu::PrimitiveState::unclipped_depth)
+    /// ([`Features::DEPTH_CLIP_CONTROL`]).
+    pub fn depth_clip_control(mut self, enabled: bool) -> Self {
+        self.required_features
+            .set(Features::DEPTH_CLIP_CONTROL, enabled);
+        self
+    }
+
+    /// Requests support for blending with a second output of the fragment shader
+    /// ([`Features::DUAL_SOURCE_BLENDING`]), as configured by [`GpuManager::dual_source_blend_state`].
+    pub fn dual_source_blending(mut self, enabled: bool) -> Self {
+        self.required_features
+            .set(Features::DUAL_SOURCE_BLENDING, enabled);
+        self
+    }
+
+    /// Requests support for clearing textures outside of a render pass through
+    /// [`CommandEncoder::clear_texture`](wgpu::CommandEncoder::clear_texture)
+    /// ([`Features::CLEAR_TEXTURE`]), or [`GpuManager::clear_texture`].
+    pub fn clear_texture(mut self, enabled: bool) -> Self {
+        self.required_features.set(Features::CLEAR_TEXTURE, enabled);
+        self
+    }
+
+    /// Requests support for mapping buffers with any usage, not only staging buffers
+    /// ([`Features::MAPPABLE_PRIMARY_BUFFERS`]).
+    ///
+    /// This avoids a staging copy for buffers the CPU updates frequently, which is faster on integrated
+    /// GPUs sharing memory with the CPU, but usually slower on discrete ones. Such buffers can be
+    /// created with [`GpuManager::create_mappable_buffer`].
+    pub fn mappable_primary_buffers(mut self, enabled: bool) -> Self {
+        self.required_features
+            .set(Features::MAPPABLE_PRIMARY_BUFFERS, enabled);
+        self
+    }
+
+    /// Requests support for subgroup operations in compute and fragment shaders
+    /// ([`Features::SUBGROUP`]).
+    ///
+    /// Once created, the supported subgroup sizes can be queried with
+    /// [`GpuManager::subgroup_size_range`].
+    pub fn subgroups(mut self, enabled: bool) -> Self {
+        self.required_features.set(Features::SUBGROUP, enabled);
+        self
+    }
+
+    /// Requests support for subgroup operations in vertex shaders as well
+    /// ([`Features::SUBGROUP_VERTEX`]).
+    ///
+    /// Enabling this also enables [`GpuManagerBuilder::subgroups`].
+    pub fn subgroups_in_vertex(mut self, enabled: bool) -> Self {
+        self.required_features
+            .set(Features::SUBGROUP_VERTEX, enabled);
+        if enabled {
+            self.required_features.insert(Features::SUBGROUP);
+        }
+        self
+    }
+
+    /// Requests support for bindless rendering: arrays of textures and samplers bound at once, indexed
+    /// non-uniformly and only partially bound ([`Features::TEXTURE_BINDING_ARRAY`],
+    /// [`Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING`] and
+    /// [`Features::PARTIALLY_BOUND_BINDING_ARRAY`]).
+    ///
+    /// The adapter's binding array limits are requested along with the features. Layouts using them can
+    /// be built with [`GpuManager::binding_array_layout`].
+    pub fn bindless(mut self, enabled: bool) -> Self {
+        self.required_features.set(BINDLESS_FEATURES, enabled);
+        self
+    }
+
+    /// Requests support for measuring GPU time with timestamp queries, including between passes
+    /// ([`Features::TIMESTAMP_QUERY`] and [`Features::TIMESTAMP_QUERY_INSIDE_ENCODERS`]).
+    ///
+    /// Parts of an encoder can then be measured with [`GpuManager::gpu_scope`].
+    pub fn timestamp_queries(mut self, enabled: bool) -> Self {
+        self.required_features.set(TIMESTAMP_FEATURES, enabled);
+        self
+    }
+
+    /// Creates a [`GpuManager`] *without* window display capabilities, using the configured options.
+    ///
+    /// # Errors
+    /// This will error if [`Adapter`](wgpu::Adapter) or [`Device`](wgpu::Device) creation fail, or if
+    /// the adapter doesn't support the requested features.
+    pub async fn build(self) -> Result<GpuManager<()>> {
+        self.build_headless(None, |_, _| Ok(None)).await
+    }
+
+    /// Same as [`GpuManagerBuilder::build`], but also returns how long each creation step took.
+    ///
+    /// # Errors
+    /// This will error if [`Adapter`](wgpu::Adapter) or [`Device`](wgpu::Device) creation fail, or if
+    /// the adapter doesn't support the requested features.
+    pub async fn build_timed(self) -> Result<(GpuManager<()>, CreationTimings)> {
+        let mut timings = CreationTimings::default();
+        let manager = self
+            .build_headless(Some(&mut timings), |_, _| Ok(None))
+            .await?;
+        Ok((manager, timings))
+    }
+
+    /// Creates a headless [`GpuManager`], choosing an adapter compatible with the [`Surface`] returned by
+    /// `create_surface`, if any, which is then dropped. Each step is timed into `timings`, if set.
+    async fn build_headless<'surface>(
+        self,
+        mut timings: Option<&mut CreationTimings>,
+        create_surface: impl FnMut(
+            &Instance,
+            Option<&mut CreationTimings>,
+        ) -> Result<Option<Surface<'surface>>>,
+    ) -> Result<GpuManager<()>> {
+        let (instance, _, adapter) = self
+            .request_instance_and_adapter(timings.as_deref_mut(), create_surface)
+            .await?;
+        log_adapter(&adapter);
+        let (features, dropped_features) = self.granted_features(&adapter);
+        log::trace!(target: LOG_TARGET, "Creating wgpu Device...");
+        let (device, queue) = step_async(
+            "device",
+            timings.map(|timings| &mut timings.device),
+            adapter.request_device(&device_descriptor(
+                features,
+                self.memory_hints.clone(),
+                &adapter,
+            )?),
+        )
+        .await?;
+
+        Ok(GpuManager {
+            surface_manager: (),
+            instance,
+            adapter,
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            blitter: Blitter::default(),
+            standard_samplers: OnceLock::new(),
+            encoder_pool: EncoderPool::default(),
+            profiler: Profiler::default(),
+            memory_hints: self.memory_hints,
+            strict_validation: false,
+            error_queue: None,
+            error_scopes: Arc::default(),
+            dropped_features,
+        })
+    }
+
+    /// Creates the [`Instance`], the [`Surface`] returned by `create_surface` and the [`Adapter`], trying
+    /// each entry of the backend order in turn.
+    async fn request_instance_and_adapter<'surface>(
+        &self,
+        mut timings: Option<&mut CreationTimings>,
+        mut create_surface: impl FnMut(
+            &Instance,
+            Option<&mut CreationTimings>,
+        ) -> Result<Option<Surface<'surface>>>,
+    ) -> Result<(Instance, Option<Surface<'surface>>, Adapter)> {
+        let mut backend_order = if self.backend_order.is_empty() {
+            vec![Backends::all()]
+        } else {
+            self.backend_order.clone()
+        };
+        let env_backends = if self.backends_from_env {
+            env_backends()
+        } else {
+            None
+        };
+        if let Some(backends) = env_backends {
+            backend_order.insert(0, backends);
+        }
+
+        let mut errors = Vec::new();
+        for (index, &backends) in backend_order.iter().enumerate() {
+            let instance = GpuManager::<()>::create_instance(
+                backends,
+                self.instance_flags,
+                timings.as_deref_mut().map(|timings| &mut timings.instance),
+            );
+            let attempt = async {
+                let surface = create_surface(&instance, timings.as_deref_mut())?;
+                log::trace!(target: LOG_TARGET, "Creating wgpu Adapter...");
+                let adapter = step_async(
+                    "adapter",
+                    timings.as_deref_mut().map(|timings| &mut timings.adapter),
+                    self.request_adapter(&instance, surface.as_ref()),
+                )
+                .await?;
+                Ok::<_, anyhow::Error>((surface, adapter))
+            };
+            match attempt.await {
+                Ok((surface, adapter)) => return Ok((instance, surface, adapter)),
+                Err(error) if backend_order.len() == 1 => return Err(error),
+                Err(error) if index == 0 && env_backends.is_some() => {
+                    log::warn!(
+                        target: LOG_TARGET,
+                        "Couldn't get an adapter for the WGPU_BACKEND backends {backends:?} ({error}), falling back to {:?}.",
+                        &backend_order[1..]
+                    );
+                    errors.push(format!("\n  - {backends:?} (WGPU_BACKEND): {error}"));
+                }
+                Err(error) => {
+                    log::warn!(target: LOG_TARGET, "Couldn't get an adapter for {backends:?}: {error}");
+                    errors.push(format!("\n  - {backends:?}: {error}"));
+                }
+            }
+        }
+        bail!(
+            "Couldn't get an adapter from any backend:{}",
+            errors.concat()
+        );
+    }
+
+    /// Returns the features to request from `adapter`, which are the required ones along with the desired
+    /// ones it supports, and the desired features it doesn't support.
+    fn granted_features(&self, adapter: &Adapter) -> (Features, Features) {
+        let desired = self.desired_features.difference(self.required_features);
+        let dropped = desired.difference(adapter.features());
+        if !dropped.is_empty() {
+            log::debug!(target: LOG_TARGET, "The adapter doesn't support the desired features {dropped:?}, not requesting them.");
+        }
+        (
+            self.required_features | desired.difference(dropped),
+            dropped,
+        )
+    }
+
+    /// Chooses the [`Adapter`], retrying with a backoff when none is found.
+    async fn request_adapter(
+        &self,
+        instance: &Instance,
+        compatible_surface: Option<&Surface<'_>>,
+    ) -> Result<Adapter> {
+        let mut delay = Duration::from_millis(50);
+        let mut attempt = 1;
+        loop {
+            match self.choose_adapter(instance, compatible_surface).await {
+                Err(error) if attempt <= self.adapter_retries => {
+                    log::debug!(
+                        target: LOG_TARGET,
+                        "Adapter request attempt {attempt} failed ({error}), retrying in {delay:?}..."
+                    );
+                    #[cfg(not(target_arch = "wasm32"))]
+                    sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Chooses the [`Adapter`], following the [`DeviceType`] preference if one was set.
+    async fn choose_adapter(
+        &self,
+        instance: &Instance,
+        compatible_surface: Option<&Surface<'_>>,
+    ) -> Result<Adapter> {
+        if !self.device_type_preference.is_empty() {
+            let rank = |adapter: &Adapter| {
+                let device_type = adapter.get_info().device_type;
+                self.device_type_preference
+                    .iter()
+                    .position(|preferred| *preferred == device_type)
+                    .unwrap_or(self.device_type_preference.len())
+            };
+            let preferred = instance
+                .enumerate_adapters(Backends::all())
+                .into_iter()
+                .filter(|adapter| {
+                    compatible_surface.is_none_or(|surface| adapter.is_surface_supported(surface))
+                })
+                .min_by_key(rank);
+            match preferred {
+                Some(adapter) => return Ok(adapter),
+                None => log::warn!(
+                    target: LOG_TARGET,
+                    "No enumerated adapter is compatible, falling back to the default adapter selection."
+                ),
+            }
+        }
+
+        Ok(instance
+            .request_adapter(&RequestAdapterOptions {
+                compatible_surface,
+                ..Default::default()
+            })
+            .await?)
+    }
+}
+
+/// Returns the backends named by the `WGPU_BACKEND` environment variable that were compiled in, or
+/// [`None`] if it is unset or names none of them.
+fn env_backends() -> Option<Backends> {
+    let requested = Backends::from_env()?;
+    let available = Instance::enabled_backend_features();
+    let unavailable = requested - available;
+    if !unavailable.is_empty() {
+        log::warn!(target: LOG_TARGET, "Ignoring the WGPU_BACKEND backends {unavailable:?}, which weren't compiled in.");
+    }
+    let backends = requested & available;
+    if backends.is_empty() {
+        log::warn!(target: LOG_TARGET, "WGPU_BACKEND names no available backend, ignoring it.");
+        return None;
+    }
+    log::debug!(target: LOG_TARGET, "Trying the WGPU_BACKEND backends {backends:?} first.");
+    Some(backends)
+}
+
+/// Validates `required_features` against `adapter` and builds the matching [`DeviceDescriptor`].
+pub(crate) fn device_descriptor(
+    required_features: Features,
+    memory_hints: MemoryHints,
+    adapter: &Adapter,
+) -> Result<DeviceDescriptor<'static>> {
+    let missing = required_features.difference(adapter.features());
+    if !missing.is_empty() {
+        bail!(missing_features_message(adapter, missing));
+    }
+
+    let mut required_limits = Limits::default();
+    if required_features.contains(Features::SUBGROUP) {
+        let adapter_limits = adapter.limits();
+        required_limits.min_subgroup_size = adapter_limits.min_subgroup_size;
+        required_limits.max_subgroup_size = adapter_limits.max_subgroup_size;
+    }
+    if required_features.contains(Features::TEXTURE_BINDING_ARRAY) {
+        let adapter_limits = adapter.limits();
+        required_limits.max_binding_array_elements_per_shader_stage =
+            adapter_limits.max_binding_array_elements_per_shader_stage;
+        required_limits.max_binding_array_sampler_elements_per_shader_stage =
+            adapter_limits.max_binding_array_sampler_elements_per_shader_stage;
+    }
+
+    Ok(DeviceDescriptor {
+        required_features,
+        required_limits,
+        memory_hints,
+        ..Default::default()
+    })
+}
+
+/// Logs which adapter was chosen, warning if it renders in software.
+pub(crate) fn log_adapter(adapter: &Adapter) {
+    let info = adapter.get_info();
+    log::debug!(target: LOG_TARGET, "Using adapter \"{}\" ({}).", info.name, info.backend);
+    if info.device_type == DeviceType::Cpu {
+        log::warn!(
+            target: LOG_TARGET,
+            "The adapter \"{}\" renders in software, performance will be much lower than on a GPU.",
+            info.name
+        );
+    }
+}
+
+/// How many times requesting an adapter is retried by default.
+const DEFAULT_ADAPTER_RETRIES: u32 = 2;
+
+/// The features requested by [`GpuManagerBuilder::bindless`].
+const BINDLESS_FEATURES: Features = Features::TEXTURE_BINDING_ARRAY
+    .union(Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING)
+    .union(Features::PARTIALLY_BOUND_BINDING_ARRAY);
+
+/// Describes each of the `missing` features on its own line, with a hint on how to do without it
+/// when one is known.
+pub(crate) fn missing_features_message(adapter: &Adapter, missing: Features) -> String {
+    let info = adapter.get_info();
+    let mut message = format!(
+        "The adapter \"{}\" ({}) doesn't support the following requested features:",
+        info.name, info.backend
+    );
+    for (name, feature) in missing.iter_names() {
+        message.push_str("\n  - ");
+        message.push_str(name);
+        let hint = match feature {
+            Features::FLOAT32_FILTERABLE => {
+                "use a non-filtering sampler, or a 16 bit float texture format instead"
+            }
+            Features::DEPTH_CLIP_CONTROL => "keep depth clipping enabled",
+            Features::CLEAR_TEXTURE => "clear the texture through a render pass instead",
+            Features::SUBGROUP | Features::SUBGROUP_VERTEX => {
+                "use workgroup shared memory instead of subgroup operations"
+            }
+            Features::TEXTURE_BINDING_ARRAY
+            | Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+            | Features::PARTIALLY_BOUND_BINDING_ARRAY => {
+                "bind the textures separately, or combine them in a single 2D array texture"
+            }
+            Features::TIMESTAMP_QUERY | Features::TIMESTAMP_QUERY_INSIDE_ENCODERS => {
+                "measure frame times on the CPU instead"
+            }
+            Features::MAPPABLE_PRIMARY_BUFFERS => {
+                "write to the buffers through `Queue::write_buffer` instead"
+            }
+            _ => continue,
+        };
+        message.push_str(": ");
+        message.push_str(hint);
+    }
+    message.push_str("\nStop requesting these features, or use an adapter that supports them.");
+    message
+}
+
+#[cfg(feature = "window")]
+/// Window related options.
+///
+/// Only available when the `window` feature is activated.
+impl GpuManagerBuilder {
+    /// Sets the state the window starts in: windowed, maximized, or fullscreen. Defaults to
+    /// [`WindowMode::Windowed`].
+    ///
+    /// This replaces the modes set before, including through [`GpuManagerBuilder::maximized`] and
+    /// [`GpuManagerBuilder::exclusive_fullscreen`].
+    pub fn window_mode(mut self, mode: WindowMode) -> Self {
+        self.window_mode = mode;
+        self
+    }
+
+    /// Sets whether the window should start maximized. Defaults to `false`.
+    ///
+    /// Same as [`GpuManagerBuilder::window_mode`] with [`WindowMode::Maximized`], or with
+    /// [`WindowMode::Windowed`] if `maximized` is `false` and the window was set to start maximized.
+    pub fn maximized(mut self, maximized: bool) -> Self {
+        if maximized {
+            self.window_mode = WindowMode::Maximized;
+        } else if self.window_mode == WindowMode::Maximized {
+            self.window_mode = WindowMode::default();
+        }
+        self
+    }
+
+    /// Sets the initial inner size of the window.
+    ///
+    /// If not set, the platform decides the size.
+    pub fn inner_size(mut self, size: impl Into<Size>) -> Self {
+        self.window_attributes.inner_size = Some(size.into());
+        self
+    }
+
+    /// Sets the initial title of the window. Defaults to `"Ray tracer"`.
+    ///
+    /// It can be changed afterwards with [`GpuManager::set_title`].
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.window_attributes.title = title.into();
+        self
+    }
+
+    /// Sets whether the window can be resized by the user. Defaults to `false`.
+    ///
+    /// Pass the resulting sizes to [`GpuManager::resize`] (or [`GpuManager::handle_window_event`]) to
+    /// reconfigure the surface.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.window_attributes.resizable = resizable;
+        self
+    }
+
+    /// Makes the window resize in steps of `increments`, such as the cell size of a terminal, so the
+    /// surface dimensions stay aligned to a grid. This also makes the window resizable.
+    ///
+    /// Resize increments are ignored on iOS, Android and the web. They can be changed afterwards with
+    /// [`GpuManager::set_resize_increments`].
+    pub fn resize_increments(mut self, increments: impl Into<Size>) -> Self {
+        self.window_attributes.resize_increments = Some(increments.into());
+        self.window_attributes.resizable = true;
+        self
+    }
+
+    /// Sets whether the window should have decorations, such as a border and title bar. Defaults to
+    /// `true`.
+    ///
+    /// Disable them to draw your own window chrome.
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.window_attributes.decorations = decorations;
+        self
+    }
+
+    /// Sets the initial theme of the window. Defaults to [`None`], which follows the system theme.
+    ///
+    /// It can be changed afterwards with [`GpuManager::set_theme`].
+    pub fn theme(mut self, theme: Option<Theme>) -> Self {
+        self.window_attributes.preferred_theme = theme;
+        self
+    }
+
+    /// Sets whether the window should be kept hidden until the first frame is acquired through
+    /// [`GpuManager::acquire_frame`]. Defaults to `false`.
+    ///
+    /// This avoids showing the window before anything was drawn to it.
+    pub fn defer_visible(mut self, defer: bool) -> Self {
+        self.defer_visible = defer;
+        self
+    }
+
+    /// Sets whether configuring the [`Surface`](wgpu::Surface) is deferred until the first frame is
+    /// acquired through [`GpuManager::acquire_frame`] (or [`GpuManager::render`]). Defaults to `false`.
+    ///
+    /// The configuration is still created when building, and can be inspected through
+    /// [`WindowManager::config`](crate::WindowManager::config). On some platforms, configuring the
+    /// surface before the window received its first
+    /// [`RedrawRequested`](winit::event::WindowEvent::RedrawRequested) event causes glitches, such as a
+    /// wrongly sized first frame. Acquiring the first frame from that event avoids them. Changes made in
+    /// the meantime, for example by [`GpuManager::resize`], are applied by that first configuration.
+    pub fn lazy_configure(mut self, lazy: bool) -> Self {
+        self.lazy_configure = lazy;
+        self
+    }
+
+    /// Limits the number of frames rendered through [`GpuManager::render`] that can be in flight at
+    /// once, tracked by a [`FrameTracker`](crate::FrameTracker).
+    ///
+    /// By default, frames aren't tracked.
+    pub fn max_frames_in_flight(mut self, max_frames_in_flight: usize) -> Self {
+        self.max_frames_in_flight = Some(max_frames_in_flight);
+        self
+    }
+
+    /// Sets the number of frames the [`FrameStats`] of [`GpuManager::frame_stats`] are computed over.
+    /// Defaults to 60.
+    pub fn frame_stats_window(mut self, frames: usize) -> Self {
+        self.frame_stats_window = frames;
+        self
+    }
+
+    /// Sets whether the sRGB variant of the surface format should be added to the
+    /// [`view_formats`](wgpu::SurfaceConfiguration::view_formats). Defaults to `false`.
+    ///
+    /// This allows rendering to the surface in linear space, while also creating sRGB views of the
+    /// frames, for example to composite a UI that expects one. A warning is logged if the chosen surface
+    /// format has no sRGB variant.
+    pub fn auto_srgb_views(mut self, enabled: bool) -> Self {
+        self.auto_srgb_views = enabled;
+        self
+    }
+
+    /// Sets exactly the usages the surface textures are configured with, instead of the default ones.
+    ///
+    /// By default, the surface is configured for [`TextureUsages::RENDER_ATTACHMENT`], along with
+    /// [`TextureUsages::COPY_DST`] when the surface supports it. With an override, nothing is added to
+    /// `usage`, which allows for example presenting the output of a compute shader through
+    /// [`TextureUsages::STORAGE_BINDING`]. Keep [`TextureUsages::RENDER_ATTACHMENT`] in it to draw
+    /// through [`GpuManager::render`] with a render scale other than 1.
+    ///
+    /// Building errors if the surface doesn't support every usage in `usage`.
+    pub fn surface_usage(mut self, usage: TextureUsages) -> Self {
+        self.surface_usage = Some(usage);
+        self
+    }
+
+    /// Sets whether the surface should use the format the backend prefers, the first one reported by
+    /// [`Surface::get_capabilities`](wgpu::Surface::get_capabilities). Defaults to `false`.
+    ///
+    /// By default, [`TextureFormat::Rgba8Unorm`](wgpu::TextureFormat::Rgba8Unorm) is used if supported,
+    /// then [`TextureFormat::Bgra8Unorm`](wgpu::TextureFormat::Bgra8Unorm), and building fails if
+    /// neither is. This keeps the format predictable across platforms, and linear, so shaders are
+    /// responsible for any sRGB encoding.
+    ///
+    /// The preferred format is usually the one the compositor uses natively, which can avoid a
+    /// conversion when presenting, and is available on every platform. But it differs between
+    /// platforms, and is often an sRGB format (such as `Bgra8UnormSrgb`), in which case writes to the
+    /// frames are encoded to sRGB automatically. Pipelines must then be created for
+    /// [`WindowManager::config`](crate::WindowManager::config)'s format rather than a hardcoded one.
+    pub fn preferred_surface_format(mut self, preferred: bool) -> Self {
+        self.preferred_surface_format = preferred;
+        self
+    }
+
+    /// Sets the [`ColorSpace`] to present frames in. Defaults to [`ColorSpace::Srgb`].
+    ///
+    /// This overrides the surface format with the one selecting `color_space`. If the surface doesn't
+    /// support it, a warning is logged and the sRGB format is kept. The color space in use can be
+    /// checked with [`GpuManager::color_space`].
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Makes the surface use `format`, such as the format of an offscreen target, so that the same
+    /// pipelines can render to both.
+    ///
+    /// This takes precedence over [`GpuManagerBuilder::preferred_surface_format`] and
+    /// [`GpuManagerBuilder::color_space`]. Building errors if the surface doesn't support `format`,
+    /// listing the formats it supports.
+    pub fn match_format(mut self, format: TextureFormat) -> Self {
+        self.match_format = Some(format);
+        self
+    }
+
+    /// Starts the window in exclusive fullscreen on the monitor at `monitor_index`, using its video mode
+    /// at `mode_index`.
+    ///
+    /// Same as [`GpuManagerBuilder::window_mode`] with [`WindowMode::ExclusiveFullscreen`]. Indices are
+    /// the same as for [`GpuManager::available_video_modes`] and
+    /// [`GpuManager::set_exclusive_fullscreen`]. Building errors if either index is out of range.
+    pub fn exclusive_fullscreen(mut self, monitor_index: usize, mode_index: usize) -> Self {
+        self.window_mode = WindowMode::ExclusiveFullscreen {
+            monitor_index,
+            mode_index,
+        };
+        self
+    }
+
+    /// Same as [`GpuManagerBuilder::build`], but only chooses an adapter that can present to windows, for
+    /// headless work that may be displayed later.
+    ///
+    /// wgpu can only tell whether an adapter can present to a given [`Surface`], so a hidden 1x1 window
+    /// and its surface are created through `event_loop` to choose the adapter, then dropped. Turning the
+    /// manager into a windowed one through [`GpuManager::attach_window`] then keeps the same
+    /// [`Device`](wgpu::Device), and every resource created from it, at least for windows on the same
+    /// display. The window related options are ignored.
+    ///
+    /// Pure compute selection, with [`GpuManagerBuilder::build`], considers every adapter, including
+    /// those that aren't connected to a display, such as compute-only datacenter GPUs or headless
+    /// software renderers, which may be faster or more suitable. Prefer it when presenting is unlikely:
+    /// [`GpuManager::attach_window`] can still switch to a presentable adapter, at the cost of recreating
+    /// the device and its resources.
+    ///
+    /// # Errors
+    /// This will error if 1) the hidden window or its [`Surface`] can't be created, 2) no adapter can
+    /// present to it, 3) [`Device`](wgpu::Device) creation fails, or 4) the adapter doesn't support the
+    /// requested features.
+    pub async fn build_presentable(
+        self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+    ) -> Result<GpuManager<()>> {
+        log::trace!(target: LOG_TARGET, "Creating hidden window to choose a presentable adapter...");
+        let window = Arc::new(GpuManager::<WindowManager>::create_window(
+            event_loop,
+            Window::default_attributes()
+                .with_visible(false)
+                .with_inner_size(PhysicalSize::new(1, 1)),
+        )?);
+        self.build_headless(None, |instance, timings| {
+            log::trace!(target: LOG_TARGET, "Creating Surface...");
+            let surface = step(
+                "surface",
+                timings.map(|timings| timings.surface.get_or_insert_default()),
+                || instance.create_surface(window.clone()),
+            )?;
+            Ok(Some(surface))
+        })
+        .await
+    }
+
+    /// Creates a [`GpuManager`] along with a [`Window`](winit::window::Window) that it will be able to
+    /// display to, using the configured options.
+    ///
+    /// Call this inside the [`ApplicationHandler::resumed`](winit::application::ApplicationHandler::resumed) function.
+    ///
+    /// # Errors
+    /// This will error if 1) [`Adapter`](wgpu::Adapter) or [`Device`](wgpu::Device) creation fail,
+    /// 2) [`Surface`](wgpu::Surface) configuration fails, or 3) the adapter doesn't support the requested
+    /// features.
+    pub async fn build_with_window<'window>(
+        self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+    ) -> Result<GpuManager<WindowManager<'window>>> {
+        self.create_with_window(event_loop, None).await
+    }
+
+    /// Same as [`GpuManagerBuilder::build_with_window`], but also returns how long each creation step
+    /// took.
+    ///
+    /// # Errors
+    /// This will error if 1) [`Adapter`](wgpu::Adapter) or [`Device`](wgpu::Device) creation fail,
+    /// 2) [`Surface`](wgpu::Surface) configuration fails, or 3) the adapter doesn't support the requested
+    /// features.
+    pub async fn build_with_window_timed<'window>(
+        self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+    ) -> Result<(GpuManager<WindowManager<'window>>, CreationTimings)> {
+        let mut timings = CreationTimings::default();
+        let manager = self
+            .create_with_window(event_loop, Some(&mut timings))
+            .await?;
+        Ok((manager, timings))
+    }
+
+    /// Creates the window, then the [`GpuManager`] around it, timing each step into `timings` if set.
+    async fn create_with_window<'window>(
+        self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        timings: Option<&mut CreationTimings>,
+    ) -> Result<GpuManager<WindowManager<'window>>> {
+        let mut window_attributes = self.window_attributes.clone();
+        if self.defer_visible {
+            window_attributes.visible = false;
+        }
+        self.window_mode.apply(&mut window_attributes, event_loop)?;
+        let window = Arc::new(GpuManager::<WindowManager>::create_window(
+            event_loop,
+            window_attributes,
+        )?);
+        let show_on_first_frame = self.defer_visible;
+        self.build_around_window(window, show_on_first_frame, timings)
+            .await
+    }
+
+    /// Creates a [`GpuManager`] displaying to an already existing `window`, using the configured
+    /// options.
+    ///
+    /// This is useful when the window is shared with other parts of the application. The window related
+    /// options (such as [`GpuManagerBuilder::title`] or [`GpuManagerBuilder::defer_visible`]) are ignored,
+    /// since the window isn't created by the manager.
+    ///
+    /// # Errors
+    /// This will error if 1) [`Adapter`](wgpu::Adapter) or [`Device`](wgpu::Device) creation fail,
+    /// 2) [`Surface`](wgpu::Surface) configuration fails, or 3) the adapter doesn't support the requested
+    /// features.
+    pub async fn build_from_window<'window>(
+        self,
+        window: Arc<Window>,
+    ) -> Result<GpuManager<WindowManager<'window>>> {
+        self.build_around_window(window, false, None).await
+    }
+
+    /// Same as [`GpuManagerBuilder::build_from_window`], but also returns how long each creation step
+    /// took.
+    ///
+    /// # Errors
+    /// This will error if 1) [`Adapter`](wgpu::Adapter) or [`Device`](wgpu::Device) creation fail,
+    /// 2) [`Surface`](wgpu::Surface) configuration fails, or 3) the adapter doesn't support the requested
+    /// features.
+    pub async fn build_from_window_timed<'window>(
+        self,
+        window: Arc<Window>,
+    ) -> Result<(GpuManager<WindowManager<'window>>, CreationTimings)> {
+        let mut timings = CreationTimings::default();
+        let manager = self
+            .build_around_window(window, false, Some(&mut timings))
+            .await?;
+        Ok((manager, timings))
+    }
+
+    /// Creates the surface for `window`, then the rest of the [`GpuManager`], timing each step into
+    /// `timings` if set.
+    async fn build_around_window<'window>(
+        self,
+        window: Arc<Window>,
+        show_on_first_frame: bool,
+        mut timings: Option<&mut CreationTimings>,
+    ) -> Result<GpuManager<WindowManager<'window>>> {
+        let (instance, surface, adapter) = self
+            .request_instance_and_adapter(timings.as_deref_mut(), |instance, timings| {
+                log::trace!(target: LOG_TARGET, "Creating Surface...");
+                let surface = step(
+                    "surface",
+                    timings.map(|timings| timings.surface.get_or_insert_default()),
+                    || instance.create_surface(window.clone()),
+                )?;
+                Ok(Some(surface))
+            })
+            .await?;
+        let surface = surface.expect("a surface is created along with every instance");
+        log_adapter(&adapter);
+        let (features, dropped_features) = self.granted_features(&adapter);
+        log::trace!(target: LOG_TARGET, "Creating wgpu Device...");
+        let (device, queue) = step_async(
+            "device",
+            timings.as_deref_mut().map(|timings| &mut timings.device),
+            adapter.request_device(&device_descriptor(
+                features,
+                self.memory_hints.clone(),
+                &adapter,
+            )?),
+        )
+        .await?;
+
+        let config = step(
+            "surface_configuration",
+            timings.map(|timings| timings.surface_configuration.insert(Duration::ZERO)),
+            || {
+                let mut config = GpuManager::<WindowManager>::create_surface_configuration(
+                    &surface,
+                    &adapter,
+                    &device,
+                    &window,
+                    self.preferred_surface_format,
+                )?;
+                let supported_formats = surface.get_capabilities(&adapter).formats;
+                self.color_space.apply(&mut config, &supported_formats);
+                if let Some(format) = self.match_format {
+                    if !supported_formats.contains(&format) {
+                        bail!(
+                            "The surface doesn't support the requested {format:?} format. Supported formats are {supported_formats:?}."
+                        );
+                    }
+                    log::trace!(target: LOG_TARGET, "Using the requested {format:?} surface format...");
+                    config.format = format;
+                    config.view_formats.clear();
+                }
+                if let Some(usage) = self.surface_usage {
+                    let supported = surface.get_capabilities(&adapter).usages;
+                    if !supported.contains(usage) {
+                        bail!(
+                            "The surface doesn't support the {:?} usages. Supported usages are {supported:?}.",
+                            usage.difference(supported)
+                        );
+                    }
+                    log::trace!(target: LOG_TARGET, "Overriding the surface usages with {usage:?}...");
+                    config.usage = usage;
+                }
+                if self.auto_srgb_views {
+                    let srgb_format = config.format.add_srgb_suffix();
+                    if srgb_format == config.format {
+                        log::warn!(
+                            target: LOG_TARGET,
+                            "Surface format {:?} has no sRGB variant, no sRGB view format was added.",
+                            config.format
+                        );
+                    } else {
+                        log::trace!(target: LOG_TARGET, "Adding {srgb_format:?} to the surface view formats...");
+                        config.view_formats.push(srgb_format);
+                    }
+                }
+                if let Some(preset) = self.preset {
+                    log::debug!(target: LOG_TARGET, "Applying the {preset:?} preset.");
+                    let present_modes = surface.get_capabilities(&adapter).present_modes;
+                    config.present_mode = preset.present_mode(&present_modes);
+                    config.desired_maximum_frame_latency = preset.frame_latency();
+                }
+                if self.lazy_configure {
+                    log::debug!(target: LOG_TARGET, "Deferring Surface configuration until the first frame.");
+                } else if config.width == 0 || config.height == 0 {
+                    log::debug!(
+                        target: LOG_TARGET,
+                        "The window has no size yet, deferring Surface configuration until it is resized."
+                    );
+                } else {
+                    log::trace!(target: LOG_TARGET, "Configuring Surface...");
+                    surface.configure(&device, &config);
+                }
+                Ok::<_, anyhow::Error>(config)
+            },
+        )?;
+
+        Ok(GpuManager {
+            surface_manager: WindowManager {
+                monitor: window.current_monitor(),
+                window,
+                surface,
+                config,
+                show_on_first_frame,
+                configure_on_first_frame: self.lazy_configure,
+                frame_tracker: self.max_frames_in_flight.map(FrameTracker::new),
+                last_present: None,
+                frame_stats: FrameStats::new(self.frame_stats_window),
+                needs_redraw: true,
+                render_scale: self.preset.map_or(1.0, Preset::render_scale),
+                scaled_target: None,
+                preferred_format: self.preferred_surface_format,
+                power_aware_present_mode: None,
+                on_battery: false,
+            },
+            instance,
+            adapter,
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            blitter: Blitter::default(),
+            standard_samplers: OnceLock::new(),
+            encoder_pool: EncoderPool::default(),
+            profiler: Profiler::default(),
+            memory_hints: self.memory_hints,
+            strict_validation: false,
+            error_queue: None,
+            error_scopes: Arc::default(),
+            dropped_features,
+        })
+    }
+}
@@ -0,0 +1,99 @@
+//! Choosing the color space frames are presented in, for wide gamut displays.
+//!
+//! Only available when the `window` feature is activated.
+
+use wgpu::{SurfaceConfiguration, TextureFormat};
+
+use crate::{GpuManager, LOG_TARGET, WindowManager};
+
+/// The color space a windowed [`GpuManager`] presents its frames in, requested through
+/// [`GpuManagerBuilder::color_space`](crate::GpuManagerBuilder::color_space).
+///
+/// wgpu doesn't let surfaces pick a color space directly: it derives it from the surface format. So
+/// each color space maps to the format that selects it, and is supported where the surface reports the
+/// format. This is meaningful on:
+/// - Vulkan, with the `VK_EXT_swapchain_colorspace` extension (Android, and Windows or Linux with HDR
+///   enabled);
+/// - Metal, on displays with extended dynamic range (most recent Macs, iPhones and iPads);
+/// - DX12, where float swapchains use the scRGB color space.
+///
+/// GL and the web always present in sRGB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// Standard sRGB, with the format chosen as described in
+    /// [`GpuManagerBuilder::preferred_surface_format`](crate::GpuManagerBuilder::preferred_surface_format).
+    #[default]
+    Srgb,
+    /// Extended linear sRGB (also known as scRGB), through an
+    /// [`Rgba16Float`](TextureFormat::Rgba16Float) surface.
+    ///
+    /// Values are linear and share the primaries of sRGB, but aren't clamped to `[0, 1]`: values outside
+    /// of it reach wider gamuts such as Display P3, and values above 1 are brighter than SDR white on HDR
+    /// displays. Colors in another gamut must be converted to linear sRGB in shaders.
+    ExtendedLinearSrgb,
+}
+
+impl ColorSpace {
+    /// Returns the surface format selecting this color space, or [`None`] if it uses the default one.
+    fn format(self) -> Option<TextureFormat> {
+        match self {
+            Self::Srgb => None,
+            Self::ExtendedLinearSrgb => Some(TextureFormat::Rgba16Float),
+        }
+    }
+
+    /// Switches `config` to the format of this color space if it is in `supported_formats`, or keeps its
+    /// sRGB format with a warning otherwise.
+    pub(crate) fn apply(
+        self,
+        config: &mut SurfaceConfiguration,
+        supported_formats: &[TextureFormat],
+    ) {
+        let Some(format) = self.format() else {
+            return;
+        };
+        if supported_formats.contains(&format) {
+            log::debug!(target: LOG_TARGET, "Presenting in {self:?} through {format:?}.");
+            config.format = format;
+            config.view_formats.clear();
+        } else {
+            log::warn!(
+                target: LOG_TARGET,
+                "The surface doesn't support {self:?} (no {format:?} format), falling back to sRGB with {:?}.",
+                config.format
+            );
+        }
+    }
+}
+
+impl GpuManager<WindowManager<'_>> {
+    /// Returns the color spaces the [`Surface`](wgpu::Surface) can present in, always including
+    /// [`ColorSpace::Srgb`].
+    ///
+    /// This reflects the current adapter and surface: on Vulkan and Metal, it may also depend on the
+    /// monitor the window is on.
+    pub fn supported_color_spaces(&self) -> Vec<ColorSpace> {
+        let formats = self
+            .surface_manager
+            .surface
+            .get_capabilities(&self.adapter)
+            .formats;
+        [ColorSpace::Srgb, ColorSpace::ExtendedLinearSrgb]
+            .into_iter()
+            .filter(|color_space| {
+                color_space
+                    .format()
+                    .is_none_or(|format| formats.contains(&format))
+            })
+            .collect()
+    }
+
+    /// Returns the color space frames are currently presented in.
+    pub fn color_space(&self) -> ColorSpace {
+        if self.surface_manager.config.format == TextureFormat::Rgba16Float {
+            ColorSpace::ExtendedLinearSrgb
+        } else {
+            ColorSpace::Srgb
+        }
+    }
+}
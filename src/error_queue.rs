@@ -0,0 +1,125 @@
+//! Collecting the uncaptured errors of the device, to be polled instead of panicking.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use wgpu::Device;
+
+use crate::{GpuManager, LOG_TARGET};
+
+/// The uncaptured errors collected since the last [`GpuManager::drain_errors`], up to a capacity.
+#[derive(Debug)]
+pub(crate) struct ErrorQueue {
+    state: Mutex<ErrorQueueState>,
+}
+
+#[derive(Debug)]
+struct ErrorQueueState {
+    errors: VecDeque<wgpu::Error>,
+    capacity: usize,
+    /// The number of errors dropped because the queue was full.
+    dropped: usize,
+}
+
+impl ErrorQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(ErrorQueueState {
+                errors: VecDeque::with_capacity(capacity),
+                capacity,
+                dropped: 0,
+            }),
+        }
+    }
+
+    fn push(&self, error: wgpu::Error) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.errors.len() == state.capacity {
+            state.errors.pop_front();
+            state.dropped += 1;
+        }
+        state.errors.push_back(error);
+    }
+
+    fn drain(&self) -> Vec<wgpu::Error> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if state.dropped > 0 {
+            log::warn!(
+                target: LOG_TARGET,
+                "{} uncaptured errors were dropped since the last drain, as more than {} were raised.",
+                state.dropped,
+                state.capacity
+            );
+            state.dropped = 0;
+        }
+        state.errors.drain(..).collect()
+    }
+
+    /// Installs the uncaptured error handler of `device`, pushing its errors into this queue.
+    pub(crate) fn install(self: &Arc<Self>, device: &Device) {
+        let queue = Arc::clone(self);
+        device.on_uncaptured_error(Box::new(move |error| {
+            log::debug!(target: LOG_TARGET, "Collected uncaptured error: {error}");
+            queue.push(error);
+        }));
+    }
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Replaces the uncaptured error handler of the [`Device`], which panics by default, with one
+    /// collecting errors to be returned by [`GpuManager::drain_errors`].
+    ///
+    /// This lets applications poll for validation or out of memory errors, for example once per frame,
+    /// and report them without crashing. Unlike [`GpuManager::capture_errors`] and
+    /// [`GpuManager::set_strict_validation`], it catches the errors of every wgpu call, but doesn't tell
+    /// which one raised them. Errors captured by an error scope aren't collected.
+    ///
+    /// At most `capacity` errors (at least one) are kept between two drains: when more are raised, the
+    /// oldest ones are dropped, and a warning is logged on the next drain. Calling this again changes the
+    /// capacity, keeping the most recent errors collected so far. Collection stays enabled for the
+    /// lifetime of the manager, including after [`GpuManager::switch_gpu`].
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let mut manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// manager.collect_errors(16);
+    ///
+    /// // Mappable buffers can't be used as storage buffers.
+    /// manager.device().create_buffer(&wgpu::BufferDescriptor {
+    ///     label: None,
+    ///     size: 16,
+    ///     usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::STORAGE,
+    ///     mapped_at_creation: false,
+    /// });
+    /// let errors = manager.drain_errors();
+    /// assert!(matches!(errors[..], [wgpu::Error::Validation { .. }]));
+    /// assert!(manager.drain_errors().is_empty());
+    /// ```
+    pub fn collect_errors(&mut self, capacity: usize) {
+        log::debug!(target: LOG_TARGET, "Collecting up to {capacity} uncaptured errors...");
+        let queue = Arc::new(ErrorQueue::new(capacity.max(1)));
+        for error in self.drain_errors() {
+            queue.push(error);
+        }
+        queue.install(&self.device);
+        self.error_queue = Some(queue);
+    }
+
+    /// Returns the uncaptured errors collected since the last call, oldest first.
+    ///
+    /// Always returns an empty [`Vec`] unless collection was enabled through
+    /// [`GpuManager::collect_errors`].
+    pub fn drain_errors(&self) -> Vec<wgpu::Error> {
+        self.error_queue
+            .as_ref()
+            .map_or_else(Vec::new, |queue| queue.drain())
+    }
+}
@@ -0,0 +1,88 @@
+//! A minimal executor, to drive the creation futures without depending on an async runtime.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Mutex;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+/// Wakes the thread blocked in [`block_on`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs `future` to completion on the current thread, parking it while the future is pending.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// The state shared between a [`sleep`] future and the thread timing it.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct SleepState {
+    elapsed: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Returns a future completing once `duration` has elapsed.
+///
+/// The delay is timed on a helper thread, so that awaiting it doesn't block the executor, whichever it
+/// is. If the thread can't be spawned, the future completes right away.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn sleep(duration: Duration) -> impl Future<Output = ()> {
+    let state = Arc::new(SleepState::default());
+    let mut spawned = false;
+    std::future::poll_fn(move |cx| {
+        if state.elapsed.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        *state
+            .waker
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(cx.waker().clone());
+        if !spawned {
+            spawned = true;
+            let timer_state = Arc::clone(&state);
+            let timer = std::thread::Builder::new()
+                .name("gpu_manager sleep".to_owned())
+                .spawn(move || {
+                    std::thread::sleep(duration);
+                    timer_state.elapsed.store(true, Ordering::Release);
+                    let waker = timer_state
+                        .waker
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .take();
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                });
+            if timer.is_err() {
+                return Poll::Ready(());
+            }
+        }
+        // The delay may have elapsed before the new waker was stored.
+        if state.elapsed.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    })
+}
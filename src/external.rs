@@ -0,0 +1,68 @@
+//! Importing textures whose memory is owned outside of wgpu, such as DMA-BUFs or shared handles.
+//!
+//! Only available when the `external-texture` feature is activated.
+
+use anyhow::{Result, bail};
+use wgpu::wgc::hal_api::HalApi;
+use wgpu::{Texture, TextureDescriptor};
+
+use crate::{GpuManager, LOG_TARGET};
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Wraps a texture created through the `A` wgpu-hal backend into a [`Texture`] usable with
+    /// [`GpuManager::device`].
+    ///
+    /// This is meant for zero copy pipelines, such as displaying decoded video frames, where the texture
+    /// memory comes from outside of wgpu. The `hal_texture` is usually created by importing that memory
+    /// with the backend API, through the device returned by [`Device::as_hal`](wgpu::Device::as_hal):
+    /// - On Vulkan, import a DMA-BUF (`VK_EXT_external_memory_dma_buf`) or a Windows handle
+    ///   (`VK_KHR_external_memory_win32`) into a `vk::Image`, and wrap it with
+    ///   `wgpu::hal::vulkan::Device::texture_from_raw`.
+    /// - On DX12, open the shared handle as an `ID3D12Resource`, and wrap it with
+    ///   `wgpu::hal::dx12::Device::texture_from_raw`.
+    /// - On Metal, wrap an `MTLTexture` backed by an `IOSurface` with
+    ///   `wgpu::hal::metal::Device::texture_from_raw`.
+    ///
+    /// The required device extensions must have been enabled when the [`Device`](wgpu::Device) was
+    /// created, which this crate doesn't do for you.
+    ///
+    /// # Safety
+    /// - `hal_texture` must have been created from the `A` device underlying [`GpuManager::device`].
+    /// - `hal_texture` must match `desc` exactly: size, mip level and sample counts, dimension, format and
+    ///   usages. wgpu trusts `desc` and doesn't validate it against the actual resource.
+    /// - The contents of `hal_texture` must be initialized.
+    /// - The external memory must stay valid for as long as the returned [`Texture`] (or anything created
+    ///   from it) is used, including work the GPU hasn't finished yet.
+    /// - Any synchronization with the external producer (for example waiting on the fence signaled by a
+    ///   video decoder) must be done by the caller, before submitting work reading the texture.
+    ///
+    /// # Errors
+    /// This will error if 1) the device doesn't use the `A` backend, or 2) `desc` requests usages its
+    /// format doesn't support.
+    pub unsafe fn import_external_texture<A: HalApi>(
+        &self,
+        hal_texture: A::Texture,
+        desc: &TextureDescriptor<'_>,
+    ) -> Result<Texture> {
+        let backend = self.adapter.get_info().backend;
+        if backend != A::VARIANT {
+            bail!(
+                "Can't import a {:?} texture into a device using the {backend:?} backend.",
+                A::VARIANT
+            );
+        }
+        let allowed_usages = self.format_features(desc.format).allowed_usages;
+        if !allowed_usages.contains(desc.usage) {
+            bail!(
+                "Texture format {:?} doesn't support the {:?} usages.",
+                desc.format,
+                desc.usage.difference(allowed_usages)
+            );
+        }
+
+        log::trace!(target: LOG_TARGET, "Importing external {:?} texture...", desc.format);
+        // SAFETY: The caller upholds the contract of `create_texture_from_hal`, and the backend was
+        // checked above.
+        Ok(unsafe { self.device.create_texture_from_hal::<A>(hal_texture, desc) })
+    }
+}
@@ -0,0 +1,595 @@
+//! Acquisition, rendering and presentation of the frames drawn to a window.
+
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use wgpu::{
+    CommandEncoder, CommandEncoderDescriptor, ComputePass, ComputePassDescriptor, Device, Extent3d,
+    PollType, Queue, SubmissionIndex, SurfaceError, SurfaceTexture, TextureDescriptor,
+    TextureDimension, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+use crate::{GpuManager, LOG_TARGET, WindowManager};
+
+/// The scales accepted by [`GpuManager::set_render_scale`].
+const RENDER_SCALE_RANGE: RangeInclusive<f32> = 0.25..=4.0;
+
+/// Keeps track of how many submitted frames the GPU hasn't finished yet.
+///
+/// Completion is detected through [`Queue::on_submitted_work_done`] callbacks, which only run when the
+/// [`Device`] is polled. [`FrameTracker::wait_for_slot`] polls it, blocking until fewer than
+/// [`FrameTracker::max_frames_in_flight`] frames are in flight. This keeps the CPU from getting too far
+/// ahead of the GPU, which would otherwise add latency (for example with
+/// [`PresentMode::Mailbox`](wgpu::PresentMode::Mailbox)).
+///
+/// Enable it for the windowed [`GpuManager`] with
+/// [`GpuManagerBuilder::max_frames_in_flight`](crate::GpuManagerBuilder::max_frames_in_flight), or use it
+/// directly around your own submissions.
+///
+/// # Examples
+/// ```
+/// use gpu_manager::{FrameTracker, GpuManager};
+///
+/// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+/// let mut tracker = FrameTracker::new(2);
+/// for _ in 0..5 {
+///     tracker.wait_for_slot(manager.device()).unwrap();
+///     let encoder = manager.device().create_command_encoder(&Default::default());
+///     let submission = manager.queue().submit([encoder.finish()]);
+///     tracker.track(manager.queue(), submission);
+///     assert!(tracker.frames_in_flight() <= 2);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FrameTracker {
+    max_frames_in_flight: usize,
+    in_flight: Arc<AtomicUsize>,
+    submissions: VecDeque<SubmissionIndex>,
+}
+
+impl FrameTracker {
+    /// Creates a tracker allowing at most `max_frames_in_flight` frames to be in flight.
+    ///
+    /// A value of `0` is treated as `1`.
+    pub fn new(max_frames_in_flight: usize) -> Self {
+        Self {
+            max_frames_in_flight: max_frames_in_flight.max(1),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            submissions: VecDeque::new(),
+        }
+    }
+
+    /// Returns the maximum number of frames allowed to be in flight.
+    pub fn max_frames_in_flight(&self) -> usize {
+        self.max_frames_in_flight
+    }
+
+    /// Returns the number of tracked frames the GPU hasn't finished yet, as of the last time the
+    /// [`Device`] was polled.
+    pub fn frames_in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Starts tracking the frame submitted as `submission`.
+    ///
+    /// Call this right after submitting the frame's work to `queue`.
+    pub fn track(&mut self, queue: &Queue, submission: SubmissionIndex) {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let in_flight = self.in_flight.clone();
+        queue.on_submitted_work_done(move || {
+            in_flight.fetch_sub(1, Ordering::AcqRel);
+        });
+        self.submissions.push_back(submission);
+    }
+
+    /// Blocks until fewer than [`FrameTracker::max_frames_in_flight`] frames are in flight.
+    ///
+    /// # Errors
+    /// This will error if polling the [`Device`] fails.
+    pub fn wait_for_slot(&mut self, device: &Device) -> Result<()> {
+        device.poll(PollType::Poll)?;
+        while self.frames_in_flight() >= self.max_frames_in_flight {
+            let Some(oldest) = self.submissions.pop_front() else {
+                break;
+            };
+            log::trace!(target: LOG_TARGET, "Too many frames in flight, waiting for the oldest one...");
+            device.poll(PollType::WaitForSubmissionIndex(oldest))?;
+        }
+        // The submissions that already finished don't need to be waited on anymore.
+        let finished = self
+            .submissions
+            .len()
+            .saturating_sub(self.frames_in_flight());
+        self.submissions.drain(..finished);
+        Ok(())
+    }
+}
+
+/// Rolling statistics over the time between consecutive frames, such as for a performance overlay.
+///
+/// Each call to [`FrameStats::record`] adds the time elapsed since the previous one, keeping only the
+/// last [`FrameStats::window`] frame times. The windowed [`GpuManager`] records every frame presented by
+/// [`GpuManager::render`], with statistics available through [`GpuManager::frame_stats`], and it can also
+/// be used directly around your own presents.
+///
+/// # Examples
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use gpu_manager::FrameStats;
+///
+/// let mut stats = FrameStats::new(2);
+/// let start = Instant::now();
+/// for (frame, millis) in [0, 10, 30, 60].into_iter().enumerate() {
+///     stats.record(start + Duration::from_millis(millis));
+///     assert_eq!(stats.frame_count(), frame.min(2));
+/// }
+/// // Only the last 2 frame times (20ms and 30ms) are kept.
+/// assert_eq!(stats.average_frame_time(), Some(Duration::from_millis(25)));
+/// assert_eq!(stats.min_frame_time(), Some(Duration::from_millis(20)));
+/// assert_eq!(stats.max_frame_time(), Some(Duration::from_millis(30)));
+/// assert_eq!(stats.fps(), Some(40.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    window: usize,
+    frame_times: VecDeque<Duration>,
+    last_frame: Option<Instant>,
+}
+
+impl Default for FrameStats {
+    /// Creates statistics over the last 60 frames.
+    fn default() -> Self {
+        Self::new(60)
+    }
+}
+
+impl FrameStats {
+    /// Creates statistics over the last `window` frames.
+    ///
+    /// A value of `0` is treated as `1`.
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            frame_times: VecDeque::with_capacity(window),
+            last_frame: None,
+        }
+    }
+
+    /// Returns the number of frames the statistics are computed over.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Changes the number of frames the statistics are computed over, dropping the oldest frame times
+    /// if there are more than `window`.
+    ///
+    /// A value of `0` is treated as `1`.
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+        let excess = self.frame_times.len().saturating_sub(self.window);
+        self.frame_times.drain(..excess);
+    }
+
+    /// Records a frame presented at `at`, adding the time since the previously recorded one.
+    pub fn record(&mut self, at: Instant) {
+        if let Some(last_frame) = self.last_frame.replace(at) {
+            if self.frame_times.len() == self.window {
+                self.frame_times.pop_front();
+            }
+            self.frame_times
+                .push_back(at.saturating_duration_since(last_frame));
+        }
+    }
+
+    /// Clears the recorded frame times, for example after a pause that shouldn't count as a frame.
+    pub fn reset(&mut self) {
+        self.frame_times.clear();
+        self.last_frame = None;
+    }
+
+    /// Returns the number of frame times the statistics are currently computed over, at most
+    /// [`FrameStats::window`].
+    pub fn frame_count(&self) -> usize {
+        self.frame_times.len()
+    }
+
+    /// Returns the average time between frames, or [`None`] if fewer than 2 frames were recorded.
+    pub fn average_frame_time(&self) -> Option<Duration> {
+        let count = u32::try_from(self.frame_times.len())
+            .ok()
+            .filter(|&count| count > 0)?;
+        Some(self.frame_times.iter().sum::<Duration>() / count)
+    }
+
+    /// Returns the shortest time between frames, or [`None`] if fewer than 2 frames were recorded.
+    pub fn min_frame_time(&self) -> Option<Duration> {
+        self.frame_times.iter().min().copied()
+    }
+
+    /// Returns the longest time between frames, or [`None`] if fewer than 2 frames were recorded.
+    pub fn max_frame_time(&self) -> Option<Duration> {
+        self.frame_times.iter().max().copied()
+    }
+
+    /// Returns the average number of frames per second, or [`None`] if fewer than 2 frames were
+    /// recorded.
+    pub fn fps(&self) -> Option<f64> {
+        let average = self.average_frame_time()?.as_secs_f64();
+        (average > 0.0).then(|| 1.0 / average)
+    }
+}
+
+/// Information about a frame rendered and presented by [`GpuManager::render`].
+#[derive(Debug, Clone)]
+pub struct PresentedFrame {
+    /// The submission containing the frame's work.
+    pub submission: SubmissionIndex,
+    /// When the frame was handed to the presentation engine.
+    ///
+    /// wgpu doesn't report when a frame actually reaches the display, so this is measured on the CPU
+    /// right after presenting. With [`PresentMode::Fifo`](wgpu::PresentMode::Fifo), acquiring frames
+    /// blocks until a swapchain image is free, so consecutive timestamps end up aligned to the display's
+    /// vertical blank, with some jitter. With other present modes, or on platforms where the compositor
+    /// adds its own buffering (such as Wayland or the web), they only reflect the CPU side of the loop.
+    pub presented_at: Instant,
+}
+
+impl GpuManager<WindowManager<'_>> {
+    /// Acquires the next [`SurfaceTexture`] to draw to.
+    ///
+    /// If the [`Surface`](wgpu::Surface) is outdated or was lost, it is reconfigured and acquisition is
+    /// retried once.
+    ///
+    /// If the window was created with [`GpuManagerBuilder::defer_visible`](crate::GpuManagerBuilder::defer_visible),
+    /// it is made visible once the first frame is acquired. Likewise, the surface is configured then if
+    /// it was built with [`GpuManagerBuilder::lazy_configure`](crate::GpuManagerBuilder::lazy_configure).
+    ///
+    /// # Errors
+    /// This will error if 1) the frame can't be acquired, even after reconfiguring the
+    /// [`Surface`](wgpu::Surface), or 2) configuration is deferred until the window has a size (see
+    /// [`GpuManager::is_configuration_deferred`]).
+    pub fn acquire_frame(&mut self) -> Result<SurfaceTexture> {
+        if self.is_configuration_deferred() {
+            bail!(
+                "The Surface isn't configured yet, since the window has no size. Call `GpuManager::resize` once it has one."
+            );
+        }
+        if self.surface_manager.configure_on_first_frame {
+            log::trace!(target: LOG_TARGET, "Configuring Surface for the first frame...");
+            self.surface_manager
+                .surface
+                .configure(&self.device, &self.surface_manager.config);
+            self.surface_manager.configure_on_first_frame = false;
+        }
+        let frame = match self.surface_manager.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(error @ (SurfaceError::Outdated | SurfaceError::Lost)) => {
+                log::debug!(target: LOG_TARGET, "{error}. Reconfiguring Surface...");
+                self.surface_manager
+                    .surface
+                    .configure(&self.device, &self.surface_manager.config);
+                match self.surface_manager.surface.get_current_texture() {
+                    Ok(frame) => frame,
+                    Err(error) => {
+                        bail!("Couldn't acquire frame after reconfiguring Surface: {error}.")
+                    }
+                }
+            }
+            Err(error) => bail!("Couldn't acquire frame: {error}."),
+        };
+
+        if self.surface_manager.show_on_first_frame {
+            log::trace!(target: LOG_TARGET, "Showing window on first frame...");
+            self.surface_manager.window.set_visible(true);
+            self.surface_manager.show_on_first_frame = false;
+        }
+        Ok(frame)
+    }
+
+    /// Acquires the next [`SurfaceTexture`] through [`GpuManager::acquire_frame`], along with a
+    /// [`TextureView`] of it to render to.
+    ///
+    /// The view uses the [`GpuManager::surface_view_format`]: the first of the configured
+    /// [`view_formats`](wgpu::SurfaceConfiguration::view_formats) if there is one, such as the sRGB
+    /// format added by [`GpuManagerBuilder::auto_srgb_views`](crate::GpuManagerBuilder::auto_srgb_views),
+    /// and the surface format otherwise.
+    ///
+    /// # Errors
+    /// This will error if the frame can't be acquired, see [`GpuManager::acquire_frame`].
+    pub fn acquire_frame_view(&mut self) -> Result<(SurfaceTexture, TextureView)> {
+        let frame = self.acquire_frame()?;
+        let view = frame.texture.create_view(&TextureViewDescriptor {
+            format: Some(self.surface_view_format()),
+            ..Default::default()
+        });
+        Ok((frame, view))
+    }
+
+    /// Acquires the next [`SurfaceTexture`] through [`GpuManager::acquire_frame`], along with a linear
+    /// and an sRGB [`TextureView`] of it, in that order.
+    ///
+    /// This allows mixing pipelines in a single frame, such as a 3D scene rendered in linear space and a
+    /// UI expecting sRGB, without reconfiguring the surface. Both formats must be available: one as the
+    /// surface format and the other in its [`view_formats`](wgpu::SurfaceConfiguration::view_formats),
+    /// as configured by [`GpuManagerBuilder::auto_srgb_views`](crate::GpuManagerBuilder::auto_srgb_views).
+    ///
+    /// # Errors
+    /// This will error if 1) the surface format has no sRGB variant, 2) the other variant isn't a
+    /// configured view format, or 3) the frame can't be acquired, see [`GpuManager::acquire_frame`].
+    pub fn acquire_frame_views(&mut self) -> Result<(SurfaceTexture, TextureView, TextureView)> {
+        let config = &self.surface_manager.config;
+        let linear_format = config.format.remove_srgb_suffix();
+        let srgb_format = config.format.add_srgb_suffix();
+        if linear_format == srgb_format {
+            bail!(
+                "The {:?} surface format has no sRGB variant, so it can't have both linear and sRGB views.",
+                config.format
+            );
+        }
+        let other_format = if config.format == linear_format {
+            srgb_format
+        } else {
+            linear_format
+        };
+        if !config.view_formats.contains(&other_format) {
+            bail!(
+                "The Surface isn't configured with {other_format:?} as a view format. Enable `GpuManagerBuilder::auto_srgb_views` to add it."
+            );
+        }
+
+        let frame = self.acquire_frame()?;
+        let view = |format| {
+            frame.texture.create_view(&TextureViewDescriptor {
+                format: Some(format),
+                ..Default::default()
+            })
+        };
+        let (linear_view, srgb_view) = (view(linear_format), view(srgb_format));
+        Ok((frame, linear_view, srgb_view))
+    }
+
+    /// Renders and presents a frame, if a redraw was requested.
+    ///
+    /// For mostly static content, frames only need to be drawn when something changed. So `render` does
+    /// nothing and returns [`None`] unless [`GpuManager::request_redraw`] was called since the last
+    /// rendered frame, without acquiring a frame or submitting any work. The first frame, and the first
+    /// one after the [`Surface`](wgpu::Surface) is reconfigured, are always drawn. To render
+    /// continuously, call [`GpuManager::request_redraw`] after each frame instead of
+    /// [`Window::request_redraw`](winit::window::Window::request_redraw). Nothing is drawn either while
+    /// configuration is deferred (see [`GpuManager::is_configuration_deferred`]).
+    ///
+    /// The next frame is acquired through [`GpuManager::acquire_frame`], and `draw` is called with a
+    /// [`CommandEncoder`] and a [`TextureView`] of it. The encoder is then submitted and the frame presented.
+    ///
+    /// When a render scale other than 1 is set through [`GpuManager::set_render_scale`], `draw` is given
+    /// a view of an intermediate target of [`GpuManager::render_size`] instead, which is then blitted to
+    /// the frame.
+    ///
+    /// When [`GpuManagerBuilder::max_frames_in_flight`](crate::GpuManagerBuilder::max_frames_in_flight) is
+    /// set, this first blocks until a frame slot is available.
+    ///
+    /// See [`PresentedFrame::presented_at`] for the limitations of the returned present timestamp.
+    ///
+    /// # Errors
+    /// This will error if 1) the frame can't be acquired, 2) waiting for a frame slot fails, or 3) the
+    /// scaled target can't be blitted to the frame. A redraw is still pending in that case.
+    #[track_caller]
+    pub fn render(
+        &mut self,
+        draw: impl FnOnce(&mut CommandEncoder, &TextureView),
+    ) -> Result<Option<PresentedFrame>> {
+        if !self.surface_manager.needs_redraw {
+            log::trace!(target: LOG_TARGET, "Frame unchanged, skipping render.");
+            return Ok(None);
+        }
+        if self.is_configuration_deferred() {
+            log::trace!(target: LOG_TARGET, "The window has no size yet, skipping render.");
+            return Ok(None);
+        }
+        if let Some(tracker) = &mut self.surface_manager.frame_tracker {
+            tracker.wait_for_slot(&self.device)?;
+        }
+
+        let frame = self.acquire_frame()?;
+        let view = frame.texture.create_view(&TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Frame encoder"),
+            });
+        match self.scaled_target() {
+            Some(target) => {
+                draw(&mut encoder, &target);
+                self.blit(&mut encoder, &target, &view)?;
+            }
+            None => draw(&mut encoder, &view),
+        }
+        let submission = self.validate("Submitting the frame", || {
+            self.queue.submit([encoder.finish()])
+        });
+
+        if let Some(tracker) = &mut self.surface_manager.frame_tracker {
+            tracker.track(&self.queue, submission.clone());
+        }
+        self.surface_manager.window.pre_present_notify();
+        frame.present();
+        let presented_at = Instant::now();
+        self.surface_manager.last_present = Some(presented_at);
+        self.surface_manager.frame_stats.record(presented_at);
+        self.surface_manager.needs_redraw = false;
+        Ok(Some(PresentedFrame {
+            submission,
+            presented_at,
+        }))
+    }
+
+    /// Renders and presents a frame like [`GpuManager::render`], first running a compute pass in the
+    /// same encoder, such as a simulation step whose results `draw` visualizes.
+    ///
+    /// `compute` records the [`ComputePass`], then `draw` is called with the encoder and a view of the
+    /// frame as in [`GpuManager::render`]. Recording both in one encoder saves a submission per frame,
+    /// and guarantees the compute pass runs first.
+    ///
+    /// There is no need to synchronize the two: wgpu tracks how each pass uses its resources, and
+    /// inserts a barrier between them when a resource changes use. So a storage buffer written by
+    /// `compute` can be read as a vertex or uniform buffer by `draw`, which will see all of its writes.
+    /// Like drawing, the compute pass is skipped when no redraw was requested; call
+    /// [`GpuManager::request_redraw`] after each frame for simulations that run continuously.
+    ///
+    /// # Errors
+    /// This will error in the same cases as [`GpuManager::render`].
+    #[track_caller]
+    pub fn compute_and_render(
+        &mut self,
+        compute: impl FnOnce(&mut ComputePass<'_>),
+        draw: impl FnOnce(&mut CommandEncoder, &TextureView),
+    ) -> Result<Option<PresentedFrame>> {
+        self.render(|encoder, view| {
+            {
+                let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("Frame compute pass"),
+                    timestamp_writes: None,
+                });
+                compute(&mut pass);
+            }
+            draw(encoder, view);
+        })
+    }
+
+    /// Marks the content as changed, so that the next call to [`GpuManager::render`] draws a frame, and
+    /// asks the window for a redraw through [`Window::request_redraw`](winit::window::Window::request_redraw).
+    pub fn request_redraw(&mut self) {
+        self.surface_manager.needs_redraw = true;
+        self.surface_manager.window.request_redraw();
+    }
+
+    /// Returns whether the next call to [`GpuManager::render`] will draw a frame.
+    pub fn needs_redraw(&self) -> bool {
+        self.surface_manager.needs_redraw
+    }
+
+    /// Sets the scale of the resolution [`GpuManager::render`] draws at, relative to the size of the
+    /// [`Surface`](wgpu::Surface).
+    ///
+    /// Frames are drawn to an intermediate target of [`GpuManager::render_size`], then blitted to the
+    /// window with linear filtering (see [`GpuManager::blit`]). This makes the rendered resolution
+    /// independent of the monitor's DPI. Scales below 1 render fewer pixels and upscale them, trading
+    /// quality for performance, while scales above 1 supersample. The blit doesn't use mipmaps, so
+    /// scales above 2 may alias.
+    ///
+    /// The scale is clamped between 0.25 and 4, with a warning when it is out of range. A scale of 1
+    /// draws directly to the frame, without an intermediate target. The target is recreated when the
+    /// surface is reconfigured, for example by [`GpuManager::resize`].
+    pub fn set_render_scale(&mut self, scale: f32) {
+        let clamped = if scale.is_nan() {
+            1.0
+        } else {
+            scale.clamp(*RENDER_SCALE_RANGE.start(), *RENDER_SCALE_RANGE.end())
+        };
+        if clamped != scale {
+            log::warn!(
+                target: LOG_TARGET,
+                "Render scale must be between {} and {}, using {clamped} instead of {scale}.",
+                RENDER_SCALE_RANGE.start(),
+                RENDER_SCALE_RANGE.end()
+            );
+        }
+        if clamped != self.surface_manager.render_scale {
+            self.surface_manager.render_scale = clamped;
+            self.surface_manager.scaled_target = None;
+            self.surface_manager.needs_redraw = true;
+        }
+    }
+
+    /// Returns the scale set through [`GpuManager::set_render_scale`], or `1` if none was.
+    pub fn render_scale(&self) -> f32 {
+        self.surface_manager.render_scale
+    }
+
+    /// Returns the width and height that [`GpuManager::render`] draws at, which is the size of the
+    /// [`Surface`](wgpu::Surface) multiplied by the [`GpuManager::render_scale`].
+    pub fn render_size(&self) -> (u32, u32) {
+        let config = &self.surface_manager.config;
+        let scale = self.surface_manager.render_scale;
+        let max = self.device.limits().max_texture_dimension_2d;
+        let scaled = |length: u32| ((length as f32 * scale).round() as u32).clamp(1, max);
+        (scaled(config.width), scaled(config.height))
+    }
+
+    /// Returns the intermediate target to draw to, creating it if needed, or [`None`] when drawing
+    /// directly to the frame.
+    fn scaled_target(&mut self) -> Option<TextureView> {
+        if self.surface_manager.render_scale == 1.0 {
+            return None;
+        }
+        if let Some(target) = &self.surface_manager.scaled_target {
+            return Some(target.clone());
+        }
+
+        let (width, height) = self.render_size();
+        let format = self.surface_manager.config.format;
+        log::trace!(target: LOG_TARGET, "Creating {width}x{height} scaled render target...");
+        let usage = TextureUsages::RENDER_ATTACHMENT
+            | TextureUsages::TEXTURE_BINDING
+            | self.format_features(format).allowed_usages & TextureUsages::STORAGE_BINDING;
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("Scaled render target"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        });
+        let target = texture.create_view(&TextureViewDescriptor::default());
+        self.surface_manager.scaled_target = Some(target.clone());
+        Some(target)
+    }
+
+    /// Returns when the last frame rendered through [`GpuManager::render`] was presented.
+    ///
+    /// See [`PresentedFrame::presented_at`] for the limitations of this timestamp.
+    pub fn last_present(&self) -> Option<Instant> {
+        self.surface_manager.last_present
+    }
+
+    /// Returns the statistics over the time between the frames presented by [`GpuManager::render`].
+    ///
+    /// Since `render` skips frames when no redraw was requested, the time spent idle between frames is
+    /// included. Call [`FrameStats::reset`] through [`GpuManager::frame_stats_mut`] after such pauses,
+    /// or render continuously for meaningful statistics.
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.surface_manager.frame_stats
+    }
+
+    /// Returns the statistics over the time between the frames presented by [`GpuManager::render`], to
+    /// change their [window](FrameStats::set_window) or [reset](FrameStats::reset) them.
+    ///
+    /// The initial window can be set with
+    /// [`GpuManagerBuilder::frame_stats_window`](crate::GpuManagerBuilder::frame_stats_window).
+    pub fn frame_stats_mut(&mut self) -> &mut FrameStats {
+        &mut self.surface_manager.frame_stats
+    }
+
+    /// Returns the number of frames rendered through [`GpuManager::render`] that the GPU hasn't finished yet.
+    ///
+    /// Returns [`None`] if [`GpuManagerBuilder::max_frames_in_flight`](crate::GpuManagerBuilder::max_frames_in_flight)
+    /// wasn't set.
+    pub fn frames_in_flight(&self) -> Option<usize> {
+        self.surface_manager
+            .frame_tracker
+            .as_ref()
+            .map(FrameTracker::frames_in_flight)
+    }
+}
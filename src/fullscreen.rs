@@ -0,0 +1,205 @@
+//! Exclusive fullscreen at a chosen video mode, and the state windows start in.
+//!
+//! Only available when the `window` feature is activated.
+
+use anyhow::{Result, bail};
+use winit::dpi::{PhysicalSize, Size};
+use winit::event_loop::ActiveEventLoop;
+use winit::monitor::{MonitorHandle, VideoModeHandle};
+use winit::window::{Fullscreen, WindowAttributes};
+
+use crate::{GpuManager, LOG_TARGET, WindowManager};
+
+/// A resolution and refresh rate a monitor supports in exclusive fullscreen, as returned by
+/// [`GpuManager::available_video_modes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VideoModeInfo {
+    /// The resolution of the mode, in physical pixels.
+    pub size: PhysicalSize<u32>,
+    /// The number of bits per pixel.
+    pub bit_depth: u16,
+    /// The refresh rate of the mode, in millihertz.
+    pub refresh_rate_millihertz: u32,
+}
+
+impl From<&VideoModeHandle> for VideoModeInfo {
+    fn from(mode: &VideoModeHandle) -> Self {
+        Self {
+            size: mode.size(),
+            bit_depth: mode.bit_depth(),
+            refresh_rate_millihertz: mode.refresh_rate_millihertz(),
+        }
+    }
+}
+
+/// The state a window created by the manager starts in, set through
+/// [`GpuManagerBuilder::window_mode`](crate::GpuManagerBuilder::window_mode).
+///
+/// The modes exclude each other, so choosing one replaces the others. Defaults to
+/// [`WindowMode::Windowed`] for [`GpuManager::builder`], and to [`WindowMode::Maximized`] for
+/// [`GpuManager::with_window`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowMode {
+    /// A regular window, of `size` if set. Otherwise, the platform decides the size, or
+    /// [`GpuManagerBuilder::inner_size`](crate::GpuManagerBuilder::inner_size) does if set.
+    Windowed {
+        /// The initial inner size of the window.
+        size: Option<Size>,
+    },
+    /// A window maximized on its monitor.
+    Maximized,
+    /// A borderless window covering the current monitor, keeping its video mode.
+    BorderlessFullscreen,
+    /// Exclusive fullscreen on the monitor at `monitor_index`, using its video mode at `mode_index`.
+    ///
+    /// Indices are the same as for [`GpuManager::available_video_modes`] and
+    /// [`GpuManager::set_exclusive_fullscreen`], since video modes can only be listed once the event
+    /// loop runs. Building errors if either index is out of range.
+    ExclusiveFullscreen {
+        /// The index of the monitor.
+        monitor_index: usize,
+        /// The index of the video mode among those of the monitor.
+        mode_index: usize,
+    },
+}
+
+impl Default for WindowMode {
+    fn default() -> Self {
+        Self::Windowed { size: None }
+    }
+}
+
+impl WindowMode {
+    /// Sets the attributes starting a window in this mode, looking video modes up in `event_loop`.
+    pub(crate) fn apply(
+        &self,
+        attributes: &mut WindowAttributes,
+        event_loop: &ActiveEventLoop,
+    ) -> Result<()> {
+        attributes.maximized = false;
+        attributes.fullscreen = None;
+        match self {
+            Self::Windowed { size } => {
+                if let Some(size) = size {
+                    attributes.inner_size = Some(*size);
+                }
+            }
+            Self::Maximized => attributes.maximized = true,
+            Self::BorderlessFullscreen => {
+                attributes.fullscreen = Some(Fullscreen::Borderless(None))
+            }
+            &Self::ExclusiveFullscreen {
+                monitor_index,
+                mode_index,
+            } => {
+                let mode = video_mode(event_loop.available_monitors(), monitor_index, mode_index)?;
+                log::trace!(target: LOG_TARGET, "Starting in exclusive fullscreen with {:?}...", VideoModeInfo::from(&mode));
+                attributes.fullscreen = Some(Fullscreen::Exclusive(mode));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the monitor at `monitor_index` among `monitors`.
+fn monitor(
+    mut monitors: impl Iterator<Item = MonitorHandle>,
+    monitor_index: usize,
+) -> Result<MonitorHandle> {
+    match monitors.nth(monitor_index) {
+        Some(monitor) => Ok(monitor),
+        None => bail!("There is no monitor at index {monitor_index}."),
+    }
+}
+
+/// Returns the video mode at `mode_index` of the monitor at `monitor_index` among `monitors`.
+fn video_mode(
+    monitors: impl Iterator<Item = MonitorHandle>,
+    monitor_index: usize,
+    mode_index: usize,
+) -> Result<VideoModeHandle> {
+    let monitor = monitor(monitors, monitor_index)?;
+    match monitor.video_modes().nth(mode_index) {
+        Some(mode) => Ok(mode),
+        None => {
+            bail!("The monitor at index {monitor_index} has no video mode at index {mode_index}.")
+        }
+    }
+}
+
+impl GpuManager<WindowManager<'_>> {
+    /// Returns the video modes the monitor at `monitor_index` supports, in the order expected by
+    /// [`GpuManager::set_exclusive_fullscreen`].
+    ///
+    /// Monitors are indexed in the order of [`Window::available_monitors`](winit::window::Window::available_monitors).
+    ///
+    /// # Errors
+    /// This will error if there is no monitor at `monitor_index`.
+    pub fn available_video_modes(&self, monitor_index: usize) -> Result<Vec<VideoModeInfo>> {
+        let monitor = monitor(
+            self.surface_manager.window.available_monitors(),
+            monitor_index,
+        )?;
+        Ok(monitor
+            .video_modes()
+            .map(|mode| VideoModeInfo::from(&mode))
+            .collect())
+    }
+
+    /// Makes the window exclusively fullscreen on the monitor at `monitor_index`, using its video mode
+    /// at `mode_index` in [`GpuManager::available_video_modes`], and reconfigures the
+    /// [`Surface`](wgpu::Surface) for the mode's resolution.
+    ///
+    /// To start in exclusive fullscreen, use
+    /// [`GpuManagerBuilder::exclusive_fullscreen`](crate::GpuManagerBuilder::exclusive_fullscreen)
+    /// instead. Exclusive fullscreen isn't supported on every platform (such as Wayland or the web), in
+    /// which case the window is left as is.
+    ///
+    /// # Errors
+    /// This will error if 1) there is no monitor at `monitor_index`, or 2) it has no video mode at
+    /// `mode_index`.
+    pub fn set_exclusive_fullscreen(
+        &mut self,
+        monitor_index: usize,
+        mode_index: usize,
+    ) -> Result<()> {
+        let mode = video_mode(
+            self.surface_manager.window.available_monitors(),
+            monitor_index,
+            mode_index,
+        )?;
+        log::debug!(target: LOG_TARGET, "Entering exclusive fullscreen with {:?}.", VideoModeInfo::from(&mode));
+        let size = mode.size();
+        self.surface_manager
+            .window
+            .set_fullscreen(Some(Fullscreen::Exclusive(mode)));
+        self.resize(size);
+        Ok(())
+    }
+
+    /// Leaves fullscreen, and reconfigures the [`Surface`](wgpu::Surface) for the window's size.
+    pub fn exit_fullscreen(&mut self) {
+        log::debug!(target: LOG_TARGET, "Leaving fullscreen.");
+        self.surface_manager.window.set_fullscreen(None);
+        self.resize(self.surface_manager.window.inner_size());
+    }
+
+    /// Returns the refresh rate of the display the window is on, in hertz, for example to budget the
+    /// time each frame can take.
+    ///
+    /// In exclusive fullscreen, this is the refresh rate of the window's video mode. Otherwise, it is the
+    /// one of the current video mode of [`Window::current_monitor`](winit::window::Window::current_monitor).
+    ///
+    /// Returns [`None`] if the current monitor or its refresh rate are unknown, such as on Wayland when
+    /// the window isn't mapped yet, or on the web.
+    pub fn refresh_rate_hz(&self) -> Option<f32> {
+        let window = &self.surface_manager.window;
+        let millihertz = match window.fullscreen() {
+            Some(Fullscreen::Exclusive(mode)) => Some(mode.refresh_rate_millihertz()),
+            _ => window.current_monitor()?.refresh_rate_millihertz(),
+        };
+        millihertz
+            .filter(|&millihertz| millihertz > 0)
+            .map(|millihertz| millihertz as f32 / 1000.0)
+    }
+}
@@ -0,0 +1,103 @@
+//! Helpers to time the creation steps and wrap them in `tracing` spans when the `tracing` feature is
+//! enabled.
+
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
+
+#[cfg(feature = "tracing")]
+use crate::LOG_TARGET;
+
+/// How long each step of a [`GpuManager`](crate::GpuManager) creation took.
+///
+/// Obtained through [`GpuManager::simple_timed`](crate::GpuManager::simple_timed),
+/// [`GpuManager::with_window_timed`](crate::GpuManager::with_window_timed) or the `_timed` variants of
+/// the [`GpuManagerBuilder`](crate::GpuManagerBuilder) build functions. The other constructors don't
+/// measure anything. On `wasm32`, which has no [`Instant`], every duration is zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CreationTimings {
+    /// Creation of the [`Instance`](wgpu::Instance). When falling back through
+    /// [`GpuManagerBuilder::backends`](crate::GpuManagerBuilder::backends), this and the following
+    /// steps add up every attempt.
+    pub instance: Duration,
+    /// Creation of the [`Surface`](wgpu::Surface). `None` when created without a window.
+    pub surface: Option<Duration>,
+    /// Request of the [`Adapter`](wgpu::Adapter).
+    pub adapter: Duration,
+    /// Request of the [`Device`](wgpu::Device) and [`Queue`](wgpu::Queue).
+    pub device: Duration,
+    /// Creation and application of the [`SurfaceConfiguration`](wgpu::SurfaceConfiguration). `None`
+    /// when created without a window.
+    pub surface_configuration: Option<Duration>,
+}
+
+impl CreationTimings {
+    /// Returns the sum of all the recorded steps.
+    pub fn total(&self) -> Duration {
+        self.instance
+            + self.surface.unwrap_or_default()
+            + self.adapter
+            + self.device
+            + self.surface_configuration.unwrap_or_default()
+    }
+}
+
+/// Starts timing a step if it is `measured`.
+///
+/// The clock is only read when timings were requested, and never on `wasm32`, where
+/// [`Instant::now`] panics: timings then stay zero.
+fn start(measured: bool) -> Option<Instant> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return measured.then(Instant::now);
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = measured;
+        None
+    }
+}
+
+/// Adds the time elapsed since `start` to `elapsed`, if both are set.
+fn stop(start: Option<Instant>, elapsed: Option<&mut Duration>) {
+    if let (Some(start), Some(elapsed)) = (start, elapsed) {
+        *elapsed += start.elapsed();
+    }
+}
+
+/// Runs `f` as the creation step `name`, adding its duration to `elapsed` if set.
+pub(crate) fn step<T>(
+    name: &'static str,
+    elapsed: Option<&mut Duration>,
+    f: impl FnOnce() -> T,
+) -> T {
+    let start = start(elapsed.is_some());
+    #[cfg(feature = "tracing")]
+    let output = tracing::debug_span!(target: LOG_TARGET, "creation_step", step = name).in_scope(f);
+    #[cfg(not(feature = "tracing"))]
+    let output = {
+        let _ = name;
+        f()
+    };
+    stop(start, elapsed);
+    output
+}
+
+/// Awaits `future` as the creation step `name`, adding its duration to `elapsed` if set.
+pub(crate) async fn step_async<F: Future>(
+    name: &'static str,
+    elapsed: Option<&mut Duration>,
+    future: F,
+) -> F::Output {
+    let start = start(elapsed.is_some());
+    #[cfg(feature = "tracing")]
+    let output = future
+        .instrument(tracing::debug_span!(target: LOG_TARGET, "creation_step", step = name))
+        .await;
+    #[cfg(not(feature = "tracing"))]
+    let output = {
+        let _ = name;
+        future.await
+    };
+    stop(start, elapsed);
+    output
+}
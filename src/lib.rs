@@ -4,25 +4,162 @@
 //! creation of the common needed [`wgpu`] abstractions, such as [`Device`], [`Instance`], [`Adapter`] etc.
 //!
 //! In order to use with a winit [`Window`], the `window` feature must be enabled.
+//!
+//! For more control over the creation, use a [`GpuManagerBuilder`], obtained through [`GpuManager::builder`].
+//!
+//! With the `external-texture` feature enabled, `GpuManager::import_external_texture` can wrap
+//! textures whose memory is owned outside of wgpu, for zero copy interop with video decoders or other
+//! APIs. This relies on wgpu-hal, so it isn't available on the web.
+//!
+//! With the `naga` feature enabled, `GpuManager::reflect_layout` derives bind group layouts from the
+//! bindings a WGSL shader declares.
+//!
+//! With the `bytemuck` feature enabled, `GpuManager::uniform_bind_group` creates a uniform buffer along
+//! with its bind group layout and bind group.
+//!
+//! # Re-exports
+//! The wgpu types most signatures of this crate use, such as [`Device`], [`Queue`] and
+//! [`TextureFormat`], are re-exported from the crate root, along with the [`wgpu`] crate itself (and
+//! `winit` with the `window` feature). Importing them from here guarantees they come from the same
+//! version as this crate uses, without pinning a matching version in the application's dependencies.
+//!
+//! # Logging
+//! All messages are logged through the [`log`] facade under the [`LOG_TARGET`] target, so they can be
+//! filtered independently from the application's own messages.
+//!
+//! With the `tracing` feature enabled, each creation step (instance, surface, adapter, device and surface
+//! configuration) additionally runs inside a `tracing` span named `creation_step`, with the step
+//! recorded in its `step` field. A subscriber can use these spans to measure startup timings.
 
-use std::sync::Arc;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, OnceLock};
 
-use anyhow::{Result, bail};
+use anyhow::bail;
 use wgpu::{
-    Adapter, Backends, Device, DeviceDescriptor, Features, Instance, InstanceDescriptor, Queue,
-    RequestAdapterOptions, Surface, SurfaceConfiguration, TextureFormat, TextureUsages,
+    Adapter, AdapterInfo, Backend, Backends, DeviceType, ErrorFilter, Extent3d, Instance,
+    InstanceDescriptor, InstanceFlags, MemoryHints, Texture, TextureDescriptor, TextureDimension,
+    TextureFormatFeatures, TextureUsages, TextureView, TextureViewDescriptor,
+};
+#[cfg(feature = "window")]
+use wgpu::{CompositeAlphaMode, RequestAdapterOptions, SurfaceConfiguration};
+#[cfg(feature = "window")]
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize, Position, Size},
+    event::WindowEvent,
+    monitor::MonitorHandle,
+    raw_window_handle::{
+        DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+    },
+    window::{Theme, Window, WindowAttributes},
 };
+
+mod adapter;
+mod animation;
+mod binding;
+mod blend;
+mod blit;
+mod buffer;
+mod builder;
+#[cfg(feature = "window")]
+mod color_space;
+mod encoder;
+mod error_queue;
+mod executor;
+#[cfg(feature = "external-texture")]
+mod external;
 #[cfg(feature = "window")]
-use winit::window::{Window, WindowAttributes};
+mod frame;
+#[cfg(feature = "window")]
+mod fullscreen;
+mod instrument;
+mod memory;
+mod offscreen;
+mod pass;
+mod pipeline;
+mod png;
+#[cfg(feature = "window")]
+mod power;
+mod preset;
+mod profiler;
+#[cfg(feature = "naga")]
+mod reflect;
+mod sampler;
+mod shadow;
+mod submit;
+mod texture;
+#[cfg(feature = "bytemuck")]
+mod uniform;
+mod validation;
+
+use blit::Blitter;
+use encoder::EncoderPool;
+use error_queue::ErrorQueue;
+use profiler::Profiler;
+use validation::ErrorScopeLock;
+
+pub use binding::BindingArrayLayoutBuilder;
+pub use builder::GpuManagerBuilder;
+#[cfg(feature = "window")]
+pub use color_space::ColorSpace;
+#[cfg(feature = "window")]
+pub use frame::{FrameStats, FrameTracker, PresentedFrame};
+#[cfg(feature = "window")]
+pub use fullscreen::{VideoModeInfo, WindowMode};
+pub use instrument::CreationTimings;
+pub use memory::MemoryReport;
+pub use offscreen::OffscreenManager;
+pub use pass::RenderPassBuilder;
+#[cfg(feature = "window")]
+pub use power::PowerAwarePresentMode;
+pub use preset::Preset;
+pub use profiler::GpuScope;
+pub use sampler::StandardSamplers;
+pub use texture::{padded_bytes_per_row, unpadded_bytes_per_row};
+
+pub use wgpu;
+pub use wgpu::{Color, Device, Features, Limits, PresentMode, Queue, Surface, TextureFormat};
+#[cfg(feature = "window")]
+pub use winit;
+
+/// The result type returned by this crate's fallible functions.
+///
+/// Errors are currently [`anyhow::Error`]s, which can be displayed or given context as usual. The
+/// alias is meant to be used in signatures instead of naming the error type, so that code keeps
+/// compiling when it becomes more specific.
+pub type Result<T, E = anyhow::Error> = std::result::Result<T, E>;
+
+/// The target used by every log message emitted by this crate.
+pub const LOG_TARGET: &str = "gpu_manager";
 
 /// Manages Device creation and basic configuration.
 ///
 /// This is the main struct provided by this crate. In order to obtain a [`GpuManager`] instance, use
-/// [`GpuManager::simple`] or [`GpuManager::with_window`].
+/// [`GpuManager::simple`], [`GpuManager::with_window`] or [`GpuManager::builder`].
 pub struct GpuManager<SurfaceManager = ()> {
+    #[cfg_attr(not(feature = "window"), allow(dead_code))]
     surface_manager: SurfaceManager,
-    device: Device,
-    queue: Queue,
+    #[cfg_attr(not(feature = "window"), allow(dead_code))]
+    instance: Instance,
+    adapter: Adapter,
+    /// Shared with the handles returned by [`GpuManager::device_arc`].
+    device: Arc<Device>,
+    /// Shared with the handles returned by [`GpuManager::queue_arc`].
+    queue: Arc<Queue>,
+    blitter: Blitter,
+    standard_samplers: OnceLock<StandardSamplers>,
+    encoder_pool: EncoderPool,
+    profiler: Profiler,
+    /// The memory hints the [`Device`] was requested with, reused when switching GPUs.
+    memory_hints: MemoryHints,
+    /// Whether the work recorded and submitted through the manager is checked for validation errors.
+    strict_validation: bool,
+    /// The queue uncaptured errors are collected into, if enabled through [`GpuManager::collect_errors`].
+    error_queue: Option<Arc<ErrorQueue>>,
+    /// Serializes the error scopes pushed by the manager, including from [`GpuManager::precompile`].
+    error_scopes: Arc<ErrorScopeLock>,
+    /// The features desired through [`GpuManagerBuilder::desired_features`] that the adapter doesn't
+    /// support.
+    dropped_features: Features,
 }
 
 impl<SurfaceManager> GpuManager<SurfaceManager> {
@@ -35,17 +172,221 @@ impl<SurfaceManager> GpuManager<SurfaceManager> {
         &self.queue
     }
 
-    fn create_instance() -> Instance {
-        log::trace!("Creating wgpu Instance...");
+    /// Returns a shared handle to the contained [`wgpu::Device`], to move to worker threads.
+    ///
+    /// The manager stores its [`Device`] and [`Queue`] in an [`Arc`], and this returns a clone of it, so
+    /// every handle refers to the same device and queue, which stay alive as long as any handle does.
+    /// Handles taken before [`GpuManager::switch_gpu`] keep referring to the previous device.
+    /// They are `Send + Sync` on native platforms. On the web, with the `webgpu` backend, they are
+    /// neither `Send` nor `Sync` and can't leave the main thread.
+    ///
+    /// The recommended pattern for multithreaded recording is for each worker to create its own
+    /// [`CommandEncoder`](wgpu::CommandEncoder)s from the shared device and send back the finished
+    /// [`CommandBuffer`](wgpu::CommandBuffer)s, which a single thread then submits together, in the
+    /// order the work must run. Resources (buffers, textures, pipelines...) can also be created from any
+    /// thread and used from any other.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let workers: Vec<_> = (0..4)
+    ///     .map(|index| {
+    ///         let device = manager.device_arc();
+    ///         std::thread::spawn(move || {
+    ///             let label = format!("Worker {index}");
+    ///             let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+    ///                 label: Some(&label),
+    ///             });
+    ///             // Record the worker's passes...
+    ///             encoder.finish()
+    ///         })
+    ///     })
+    ///     .collect();
+    /// let command_buffers = workers.into_iter().map(|worker| worker.join().unwrap());
+    /// manager.queue_arc().submit(command_buffers);
+    /// ```
+    pub fn device_arc(&self) -> Arc<Device> {
+        Arc::clone(&self.device)
+    }
+
+    /// Returns a shared handle to the contained [`wgpu::Queue`], to move to worker threads. See
+    /// [`GpuManager::device_arc`] for the guarantees and the recommended multithreading pattern.
+    ///
+    /// Submitting from several threads is allowed, but the order of submissions across threads is then
+    /// unspecified.
+    pub fn queue_arc(&self) -> Arc<Queue> {
+        Arc::clone(&self.queue)
+    }
+
+    /// Returns the [`Queue`] to submit compute work to.
+    ///
+    /// wgpu creates a single queue per [`Device`], on every backend, so this is currently the same
+    /// queue as [`GpuManager::queue`]. Submitting compute work through it keeps code ready for dedicated
+    /// compute queues, should wgpu expose them.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// assert_eq!(manager.compute_queue(), manager.queue());
+    /// ```
+    pub fn compute_queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    /// Returns the [`Queue`] to submit transfers (buffer and texture uploads and copies) to.
+    ///
+    /// Like [`GpuManager::compute_queue`], this is currently the same queue as [`GpuManager::queue`].
+    pub fn transfer_queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    /// Returns information about the [`Adapter`] the [`Device`] was created from.
+    pub fn adapter_info(&self) -> AdapterInfo {
+        self.adapter.get_info()
+    }
+
+    /// Returns the [`Backend`] the [`Device`] runs on.
+    ///
+    /// When a fallback order was given to [`GpuManagerBuilder::backends`], this is the one that
+    /// succeeded.
+    pub fn backend(&self) -> Backend {
+        self.adapter.get_info().backend
+    }
+
+    /// Returns whether the [`Adapter`] renders in software on the CPU, such as llvmpipe or WARP.
+    ///
+    /// This can happen when no hardware adapter is available, and means rendering will be much slower.
+    /// A warning is also logged when such an adapter is chosen.
+    pub fn is_software_rendering(&self) -> bool {
+        self.adapter.get_info().device_type == DeviceType::Cpu
+    }
+
+    /// Returns the features `format` supports on the contained [`Device`], such as its allowed usages
+    /// and sample counts.
+    ///
+    /// The adapter specific features of `format` are only available when
+    /// [`Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] was requested. Otherwise, only the features
+    /// guaranteed by the WebGPU specification are returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let features = manager.format_features(wgpu::TextureFormat::Rgba8Unorm);
+    /// assert!(features.allowed_usages.contains(wgpu::TextureUsages::RENDER_ATTACHMENT));
+    /// ```
+    pub fn format_features(&self, format: TextureFormat) -> TextureFormatFeatures {
+        let device_features = self.device.features();
+        if device_features.contains(Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+            self.adapter.get_texture_format_features(format)
+        } else {
+            format.guaranteed_format_features(device_features)
+        }
+    }
+
+    /// Returns the range of subgroup sizes supported by the device.
+    ///
+    /// Returns [`None`] if subgroups weren't requested through [`GpuManagerBuilder::subgroups`], or if
+    /// the adapter doesn't report its subgroup sizes.
+    pub fn subgroup_size_range(&self) -> Option<RangeInclusive<u32>> {
+        if !self.device.features().contains(Features::SUBGROUP) {
+            return None;
+        }
+        let limits = self.device.limits();
+        (limits.min_subgroup_size > 0 && limits.max_subgroup_size > 0)
+            .then_some(limits.min_subgroup_size..=limits.max_subgroup_size)
+    }
+
+    /// Returns the features desired through [`GpuManagerBuilder::desired_features`] (or
+    /// [`GpuManager::simple_with_available`]) that weren't requested, since the adapter doesn't support
+    /// them.
+    pub fn dropped_features(&self) -> Features {
+        self.dropped_features
+    }
+
+    /// Runs `f`, capturing the validation errors caused by the wgpu calls it makes, and returns the
+    /// first one.
+    ///
+    /// This helps pinpointing which call is wrong, while the uncaptured error handler only reports
+    /// errors globally (and panics by default). Errors raised outside of `f` aren't affected.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let error = pollster::block_on(manager.capture_errors(|| {
+    ///     // Mappable buffers can't be used as storage buffers.
+    ///     manager.device().create_buffer(&wgpu::BufferDescriptor {
+    ///         label: None,
+    ///         size: 16,
+    ///         usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::STORAGE,
+    ///         mapped_at_creation: false,
+    ///     });
+    /// }));
+    /// assert!(matches!(error, Some(wgpu::Error::Validation { .. })));
+    ///
+    /// assert!(pollster::block_on(manager.capture_errors(|| {})).is_none());
+    /// ```
+    pub async fn capture_errors(&self, f: impl FnOnce()) -> Option<wgpu::Error> {
+        // The scope is popped when the future is created, so the lock isn't held across the await.
+        let popped = {
+            let _guard = self.error_scopes.lock();
+            self.device.push_error_scope(ErrorFilter::Validation);
+            f();
+            self.device.pop_error_scope()
+        };
+        popped.await
+    }
+
+    /// Clamps `width` and `height` to the maximum 2D texture dimension of `device`, with a warning.
+    fn clamp_size(width: u32, height: u32, device: &Device) -> (u32, u32) {
+        let max = device.limits().max_texture_dimension_2d;
+        if width > max || height > max {
+            log::warn!(
+                target: LOG_TARGET,
+                "Size {width}x{height} exceeds the maximum texture dimension of {max}, clamping it."
+            );
+        }
+        (width.min(max), height.min(max))
+    }
+
+    /// Returns the size render targets should be resized to for `width` and `height`, clamped as in
+    /// [`GpuManager::clamp_size`], or [`None`] if either is zero, since textures can't be empty.
+    fn resized_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if width == 0 || height == 0 {
+            log::trace!(target: LOG_TARGET, "Ignoring resize to the empty size {width}x{height}.");
+            return None;
+        }
+        Some(Self::clamp_size(width, height, &self.device))
+    }
+
+    fn create_instance(
+        backends: Backends,
+        flags: InstanceFlags,
+        elapsed: Option<&mut std::time::Duration>,
+    ) -> Instance {
+        log::trace!(target: LOG_TARGET, "Creating wgpu Instance for {backends:?} with {flags:?}...");
         let instance_desc = InstanceDescriptor {
-            backends: Backends::all(),
+            backends,
+            flags,
             ..Default::default()
         };
-        Instance::new(&instance_desc)
+        instrument::step("instance", elapsed, || Instance::new(&instance_desc))
     }
 }
 
 impl GpuManager<()> {
+    /// Returns a [`GpuManagerBuilder`] to configure the creation of a [`GpuManager`].
+    pub fn builder() -> GpuManagerBuilder {
+        GpuManagerBuilder::new()
+    }
+
     /// Creates a [`GpuManager`] *without* window display capabilities.
     ///
     /// To be used without a window.
@@ -62,24 +403,127 @@ impl GpuManager<()> {
     /// # Errors
     /// This will error if [`Adapter`] or [`Device`] creation fail.
     pub async fn simple() -> Result<Self> {
-        let instance = Self::create_instance();
-        log::trace!("Creating wgpu Adapter...");
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptionsBase::default())
-            .await?;
-        log::trace!("Creating wgpu Device...");
-        let (device, queue) = adapter
-            .request_device(&DeviceDescriptor {
-                required_features: Features::empty(),
-                ..Default::default()
-            })
-            .await?;
+        Self::builder().build().await
+    }
 
-        Ok(Self {
-            surface_manager: (),
-            device,
-            queue,
-        })
+    /// Same as [`GpuManager::simple`], but also requests the `desired` features the adapter supports,
+    /// and returns the ones granted.
+    ///
+    /// The desired features the adapter doesn't support are left out instead of failing creation, and
+    /// can be queried afterwards through [`GpuManager::dropped_features`]. Use
+    /// [`GpuManagerBuilder::desired_features`] for more control over the creation.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::Features;
+    ///
+    /// let desired = Features::TIMESTAMP_QUERY | Features::FLOAT32_FILTERABLE;
+    /// let (manager, granted) =
+    ///     pollster::block_on(GpuManager::simple_with_available(desired)).unwrap();
+    /// assert_eq!(granted | manager.dropped_features(), desired);
+    /// if granted.contains(Features::TIMESTAMP_QUERY) {
+    ///     // Profile the GPU work...
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if [`Adapter`] or [`Device`] creation fail.
+    pub async fn simple_with_available(desired: Features) -> Result<(Self, Features)> {
+        let manager = Self::builder().desired_features(desired).build().await?;
+        let granted = desired.difference(manager.dropped_features);
+        Ok((manager, granted))
+    }
+
+    /// Same as [`GpuManager::simple`], but also returns how long each creation step took.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let (manager, timings) = pollster::block_on(GpuManager::simple_timed()).unwrap();
+    /// assert!(timings.surface.is_none());
+    /// println!("Created the GpuManager in {:?}", timings.total());
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if [`Adapter`] or [`Device`] creation fail.
+    pub async fn simple_timed() -> Result<(Self, CreationTimings)> {
+        Self::builder().build_timed().await
+    }
+
+    /// Same as [`GpuManager::simple`], but blocks the current thread until creation is done, without
+    /// needing an async runtime.
+    ///
+    /// This suits libraries and engines that can't assume which executor, if any, their caller uses.
+    /// With the native backends (Vulkan, Metal, DX12 and OpenGL), wgpu completes the adapter and device
+    /// requests synchronously, so this returns as soon as creation is done. On the web, WebGPU only
+    /// completes them from the browser's event loop, which blocking would prevent from running, so
+    /// await [`GpuManager::simple`] there instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = GpuManager::simple_blocking().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if [`Adapter`] or [`Device`] creation fail.
+    pub fn simple_blocking() -> Result<Self> {
+        executor::block_on(Self::simple())
+    }
+
+    /// Creates a texture meant to receive the output of a compute shader and be handed to another
+    /// consumer, along with a [`TextureView`] of it.
+    ///
+    /// The texture can be written to as a storage texture, sampled in a shader and copied from
+    /// ([`TextureUsages::STORAGE_BINDING`], [`TextureUsages::TEXTURE_BINDING`] and
+    /// [`TextureUsages::COPY_SRC`]).
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let (texture, view) = manager
+    ///     .create_output_texture(256, 256, wgpu::TextureFormat::Rgba8Unorm)
+    ///     .unwrap();
+    /// assert_eq!(texture.width(), 256);
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if `format` can't be used as a storage texture.
+    pub fn create_output_texture(
+        &self,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+    ) -> Result<(Texture, TextureView)> {
+        let usage = TextureUsages::STORAGE_BINDING
+            | TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_SRC;
+        let allowed_usages = self.format_features(format).allowed_usages;
+        if !allowed_usages.contains(usage) {
+            bail!("Texture format {format:?} can't be used as a compute output texture.");
+        }
+
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("Output texture"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        Ok((texture, view))
     }
 }
 
@@ -94,42 +538,80 @@ impl<'window> GpuManager<WindowManager<'window>> {
     ///
     /// Call this inside the [`ApplicationHandler::resumed`](winit::application::ApplicationHandler::resumed) function.
     ///
+    /// The window is created maximized. Use [`GpuManagerBuilder::window_mode`] to choose a different
+    /// size or a fullscreen mode.
+    ///
     /// # Errors
     /// This will error if 1) [`Adapter`] or [`Device`] creation fail, or 2) [`Surface`] configuration fails.
     pub async fn with_window(event_loop: &winit::event_loop::ActiveEventLoop) -> Result<Self> {
-        let instance = Self::create_instance();
+        GpuManager::builder()
+            .window_mode(WindowMode::Maximized)
+            .build_with_window(event_loop)
+            .await
+    }
 
-        let window = Arc::new(Self::create_window(event_loop)?);
-        log::trace!("Creating Surface...");
-        let surface = instance.create_surface(window.clone())?;
-        log::trace!("Creating wgpu Adapter...");
+    /// Same as [`GpuManager::with_window`], but also returns how long each creation step took.
+    ///
+    /// # Errors
+    /// This will error if 1) [`Adapter`] or [`Device`] creation fail, or 2) [`Surface`] configuration fails.
+    pub async fn with_window_timed(
+        event_loop: &winit::event_loop::ActiveEventLoop,
+    ) -> Result<(Self, CreationTimings)> {
+        GpuManager::builder()
+            .window_mode(WindowMode::Maximized)
+            .build_with_window_timed(event_loop)
+            .await
+    }
+
+    /// Creates a [`GpuManager`] displaying to an already existing `window`, instead of creating its own.
+    ///
+    /// This leaves the window's ownership to the application, for example when it is shared with other
+    /// subsystems. Use [`GpuManagerBuilder::build_from_window`] for more control over the creation.
+    ///
+    /// # Errors
+    /// This will error if 1) [`Adapter`] or [`Device`] creation fail, or 2) [`Surface`] configuration fails.
+    pub async fn from_window(window: Arc<Window>) -> Result<Self> {
+        GpuManager::builder().build_from_window(window).await
+    }
+
+    /// Creates a [`GpuManager`] displaying to a `surface` of `window` that was created elsewhere, for
+    /// example by another library, instead of creating its own.
+    ///
+    /// A surface can only be used with adapters of the [`Instance`] that created it, so that `instance`
+    /// must be given too. An adapter compatible with `surface` is requested from it, then the manager
+    /// takes ownership of the instance and the surface, and configures it. The device is requested
+    /// without any feature; use [`GpuManager::from_adapter_with_window`] for more control.
+    ///
+    /// # Errors
+    /// This will error if 1) no adapter of `instance` can present to `surface`, 2) [`Device`] creation
+    /// fails, or 3) `surface` can't be configured with the device, for example because it is already
+    /// configured for another one.
+    pub async fn with_existing_surface(
+        instance: Instance,
+        surface: Surface<'window>,
+        window: Arc<Window>,
+    ) -> Result<Self> {
+        log::trace!(target: LOG_TARGET, "Creating wgpu Adapter compatible with the existing Surface...");
         let adapter = instance
             .request_adapter(&RequestAdapterOptions {
                 compatible_surface: Some(&surface),
                 ..Default::default()
             })
             .await?;
-        log::trace!("Creating wgpu Device...");
-        let (device, queue) = adapter
-            .request_device(&DeviceDescriptor {
-                required_features: Features::empty(),
-                ..Default::default()
-            })
-            .await?;
-
-        let config = Self::create_surface_configuration(&surface, &adapter, &window)?;
-        log::trace!("Configuring Surface...");
-        surface.configure(&device, &config);
+        let descriptor =
+            builder::device_descriptor(Features::empty(), MemoryHints::default(), &adapter)?;
+        let manager = GpuManager::from_adapter(instance, adapter, &descriptor).await?;
 
-        Ok(Self {
-            surface_manager: WindowManager {
-                window,
-                surface,
-                config,
-            },
-            device,
-            queue,
-        })
+        // The manager is consumed even on failure, so keep a handle to pop the scope in every case.
+        let device = manager.device.clone();
+        device.push_error_scope(ErrorFilter::Validation);
+        let manager = manager.into_windowed(window, surface);
+        let error = device.pop_error_scope().await;
+        let manager = manager?;
+        if let Some(error) = error {
+            bail!("The existing Surface can't be configured with the device: {error}");
+        }
+        Ok(manager)
     }
 
     /// Returns a reference to the contained [`SurfaceConfiguration`].
@@ -137,6 +619,98 @@ impl<'window> GpuManager<WindowManager<'window>> {
         &self.surface_manager.config
     }
 
+    /// Returns the format the [`Surface`] is configured with, which pipelines rendering directly to its
+    /// textures must target.
+    pub fn surface_format(&self) -> TextureFormat {
+        self.surface_manager.config.format
+    }
+
+    /// Creates a texture with the format and current size of the [`Surface`], such as a scratch target
+    /// for post-processing passes, along with a [`TextureView`] of it.
+    ///
+    /// The texture can be rendered to and sampled ([`TextureUsages::RENDER_ATTACHMENT`] and
+    /// [`TextureUsages::TEXTURE_BINDING`]), along with `extra_usage`, which the surface format must
+    /// support (see [`GpuManager::format_features`]). It also has the surface's view formats, and the
+    /// view uses the [`GpuManager::surface_view_format`], so pipelines drawing to the frame can draw to
+    /// it too. The texture doesn't follow the surface: recreate it after [`GpuManager::resize`]. While
+    /// configuration is deferred, it is 1x1.
+    pub fn create_matching_texture(&self, extra_usage: TextureUsages) -> (Texture, TextureView) {
+        let config = &self.surface_manager.config;
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("Surface matching texture"),
+            size: Extent3d {
+                width: config.width.max(1),
+                height: config.height.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: config.format,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING | extra_usage,
+            view_formats: &config.view_formats,
+        });
+        let view = texture.create_view(&TextureViewDescriptor {
+            format: Some(self.surface_view_format()),
+            ..Default::default()
+        });
+        (texture, view)
+    }
+
+    /// Returns the usages the [`Surface`] textures are configured with.
+    ///
+    /// By default, these are [`TextureUsages::RENDER_ATTACHMENT`], along with
+    /// [`TextureUsages::COPY_DST`] only when the surface supports it, or the usages set through
+    /// [`GpuManagerBuilder::surface_usage`].
+    pub fn surface_usage(&self) -> TextureUsages {
+        self.surface_manager.config.usage
+    }
+
+    /// Returns whether the [`Surface`] textures can be copied into, for example to blit a screenshot or
+    /// an offscreen render to them with [`CommandEncoder::copy_texture_to_texture`](wgpu::CommandEncoder::copy_texture_to_texture).
+    ///
+    /// When this is `false`, draw to the frame instead, such as through [`GpuManager::blit`].
+    pub fn surface_supports_copy_dst(&self) -> bool {
+        self.surface_usage().contains(TextureUsages::COPY_DST)
+    }
+
+    /// Returns the format of the views created by [`GpuManager::acquire_frame_view`], which pipelines
+    /// rendering to them must target.
+    ///
+    /// This is the sRGB variant of [`GpuManager::surface_format`] when it was added as a view format by
+    /// [`GpuManagerBuilder::auto_srgb_views`], or more generally the first configured
+    /// [`view_formats`](SurfaceConfiguration::view_formats). Otherwise, it is the surface format itself.
+    pub fn surface_view_format(&self) -> TextureFormat {
+        let config = &self.surface_manager.config;
+        config
+            .view_formats
+            .first()
+            .copied()
+            .unwrap_or(config.format)
+    }
+
+    /// Returns a one line summary of the current [`SurfaceConfiguration`], to paste into bug reports.
+    ///
+    /// For example:
+    /// ```text
+    /// Bgra8Unorm 1280x720, Fifo with frame latency 2, Opaque alpha, usages COPY_DST | RENDER_ATTACHMENT, view formats []
+    /// ```
+    pub fn config_summary(&self) -> String {
+        let config = &self.surface_manager.config;
+        let usages: Vec<_> = config.usage.iter_names().map(|(name, _)| name).collect();
+        format!(
+            "{:?} {}x{}, {:?} with frame latency {}, {:?} alpha, usages {}, view formats {:?}",
+            config.format,
+            config.width,
+            config.height,
+            config.present_mode,
+            config.desired_maximum_frame_latency,
+            config.alpha_mode,
+            usages.join(" | "),
+            config.view_formats
+        )
+    }
+
     /// Returns a reference to the contained [`Surface`].
     pub fn surface(&self) -> &Surface<'window> {
         &self.surface_manager.surface
@@ -147,13 +721,456 @@ impl<'window> GpuManager<WindowManager<'window>> {
         self.surface_manager.window.clone()
     }
 
+    /// Returns a reference to the contained [`Window`].
+    ///
+    /// Unlike [`GpuManager::window`], this doesn't touch the reference count, which suits per frame
+    /// queries such as [`Window::inner_size`].
+    pub fn window_ref(&self) -> &Window {
+        &self.surface_manager.window
+    }
+
+    /// Converts a color given in sRGB space into the [`Color`] to clear the surface with, so that it is
+    /// displayed as given.
+    ///
+    /// sRGB surface formats encode the values written to them, so the color is converted to linear space
+    /// first to avoid encoding it twice. Other formats store the values as they are, so the color is
+    /// passed through unchanged.
+    pub fn clear_color_srgb(&self, rgb: [f32; 3]) -> Color {
+        let convert = |channel: f32| {
+            let channel = f64::from(channel);
+            if !self.surface_manager.config.format.is_srgb() {
+                channel
+            } else if channel <= 0.04045 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Color {
+            r: convert(rgb[0]),
+            g: convert(rgb[1]),
+            b: convert(rgb[2]),
+            a: 1.0,
+        }
+    }
+
+    /// Returns the raw window handle of the contained [`Window`], to interoperate with other graphics
+    /// libraries.
+    ///
+    /// # Errors
+    /// This will error if the handle isn't available, for example on Android while the application is
+    /// suspended.
+    pub fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        self.surface_manager.window.window_handle()
+    }
+
+    /// Returns the raw display handle of the contained [`Window`], to interoperate with other graphics
+    /// libraries.
+    ///
+    /// # Errors
+    /// This will error if the handle isn't available.
+    pub fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        self.surface_manager.window.display_handle()
+    }
+
+    /// Changes the title of the contained [`Window`].
+    ///
+    /// The initial title can be set with [`GpuManagerBuilder::title`].
+    pub fn set_title(&self, title: &str) {
+        self.surface_manager.window.set_title(title);
+    }
+
+    /// Changes the theme of the contained [`Window`]. [`None`] follows the system theme.
+    ///
+    /// The initial theme can be set with [`GpuManagerBuilder::theme`].
+    pub fn set_theme(&self, theme: Option<Theme>) {
+        self.surface_manager.window.set_theme(theme);
+    }
+
+    /// Makes the contained [`Window`] resize in steps of `increments`, or freely with [`None`].
+    ///
+    /// The [`Surface`] follows the stepped sizes once they are passed to [`GpuManager::resize`]. The
+    /// initial increments can be set with [`GpuManagerBuilder::resize_increments`].
+    pub fn set_resize_increments(&self, increments: Option<Size>) {
+        self.surface_manager
+            .window
+            .set_resize_increments(increments);
+    }
+
+    /// Returns the position of the top-left corner of the contained [`Window`], including its
+    /// decorations, relative to the desktop.
+    ///
+    /// Returns [`None`] on platforms where windows can't know their position, such as Wayland, Android,
+    /// iOS or the web.
+    pub fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        self.surface_manager.window.outer_position().ok()
+    }
+
+    /// Moves the top-left corner of the contained [`Window`], including its decorations, to `position`
+    /// relative to the desktop.
+    ///
+    /// Together with [`GpuManager::outer_position`], this lets applications restore the placement of
+    /// their window across launches. This does nothing on platforms where windows can't be moved, such
+    /// as Wayland, Android or iOS.
+    pub fn set_outer_position(&self, position: impl Into<Position>) {
+        self.surface_manager.window.set_outer_position(position);
+    }
+
+    /// Reconfigures the [`Surface`] for the new size of the window.
+    ///
+    /// Call this when receiving [`WindowEvent::Resized`]. The size is
+    /// clamped to the device's [`max_texture_dimension_2d`](wgpu::Limits::max_texture_dimension_2d),
+    /// with a warning. Sizes with a zero width or height, such as those of minimized windows, are ignored.
+    ///
+    /// If configuration was deferred because the window had no size yet (see
+    /// [`GpuManager::is_configuration_deferred`]), the first non-zero size configures the [`Surface`].
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        self.resize_to(size.width, size.height);
+    }
+
+    /// Same as [`GpuManager::resize`], but with the width and height as plain integers, as for
+    /// offscreen managers.
+    pub fn resize_to(&mut self, width: u32, height: u32) {
+        let Some((width, height)) = self.resized_size(width, height) else {
+            return;
+        };
+        self.surface_manager.config.width = width;
+        self.surface_manager.config.height = height;
+        self.reconfigure();
+    }
+
+    /// Replaces the contained [`Window`] and [`Surface`] with a new surface created for `window`, and
+    /// configures it.
+    ///
+    /// On Android, the native window is destroyed when the app is suspended, and a new one is created
+    /// when it resumes. Call this from the
+    /// [`ApplicationHandler::resumed`](winit::application::ApplicationHandler::resumed) following a
+    /// suspension, with the new window. The current [`SurfaceConfiguration`] is kept, resized to the new
+    /// window, except for the parts the new surface doesn't support, as in
+    /// [`GpuManager::refresh_capabilities`].
+    ///
+    /// # Errors
+    /// This will error if 1) the [`Surface`] can't be created, 2) the [`Adapter`] can't present to it, or
+    /// 3) none of its supported formats can be used. The current surface is kept in the first two cases.
+    pub fn recreate_surface(&mut self, window: Arc<Window>) -> Result<()> {
+        log::trace!(target: LOG_TARGET, "Recreating Surface for a new window...");
+        let surface = self.instance.create_surface(window.clone())?;
+        if !self.adapter.is_surface_supported(&surface) {
+            bail!("The adapter can't present to the new window's surface.");
+        }
+        let (width, height) = Self::clamp_size(
+            window.inner_size().width,
+            window.inner_size().height,
+            &self.device,
+        );
+        self.surface_manager.monitor = window.current_monitor();
+        self.surface_manager.window = window;
+        self.surface_manager.surface = surface;
+        self.surface_manager.config.width = width;
+        self.surface_manager.config.height = height;
+        if !self.refresh_capabilities()? {
+            self.reconfigure();
+        }
+        Ok(())
+    }
+
+    /// Creates and configures an additional [`Surface`] for `window`, reusing this manager's
+    /// [`Instance`], [`Adapter`] and [`Device`].
+    ///
+    /// This is useful for secondary windows such as tooltips or popups. The caller owns the returned
+    /// surface and its configuration, and is responsible for reconfiguring it when `window` is resized.
+    /// If `window` has no size yet, the surface isn't configured, and the returned configuration has a
+    /// zero width or height (see [`GpuManager::is_configuration_deferred`]).
+    ///
+    /// # Errors
+    /// This will error if 1) the [`Surface`] can't be created, 2) the [`Adapter`] can't present to it, or
+    /// 3) [`Surface`] configuration fails.
+    pub fn create_child_surface(
+        &self,
+        window: Arc<Window>,
+    ) -> Result<(Surface<'static>, SurfaceConfiguration)> {
+        log::trace!(target: LOG_TARGET, "Creating child Surface...");
+        let surface = self.instance.create_surface(window.clone())?;
+        if !self.adapter.is_surface_supported(&surface) {
+            bail!("The adapter can't present to the child window's surface.");
+        }
+        let config = Self::create_surface_configuration(
+            &surface,
+            &self.adapter,
+            &self.device,
+            &window,
+            self.surface_manager.preferred_format,
+        )?;
+        if config.width == 0 || config.height == 0 {
+            log::debug!(target: LOG_TARGET, "The child window has no size yet, deferring its Surface configuration.");
+        } else {
+            log::trace!(target: LOG_TARGET, "Configuring child Surface...");
+            surface.configure(&self.device, &config);
+        }
+        Ok((surface, config))
+    }
+
+    /// Reacts to the window events that affect the [`Surface`], and returns whether its format, present
+    /// mode, alpha mode or usages changed.
+    ///
+    /// Forward every [`WindowEvent`] of the contained window to this. Resizes are passed to
+    /// [`GpuManager::resize`]. When the window was moved, or its scale factor changed, and it is now on
+    /// another monitor, [`GpuManager::on_monitor_changed`] is called. Other events are ignored.
+    ///
+    /// # Errors
+    /// This will error if none of the formats supported on the new monitor can be used.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) -> Result<bool> {
+        match event {
+            WindowEvent::Resized(size) => {
+                self.resize(*size);
+                Ok(false)
+            }
+            WindowEvent::Moved(_) | WindowEvent::ScaleFactorChanged { .. } => {
+                let monitor = self.surface_manager.window.current_monitor();
+                if monitor != self.surface_manager.monitor {
+                    return self.on_monitor_changed();
+                }
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Refreshes the [`Surface`] capabilities through [`GpuManager::refresh_capabilities`] after the
+    /// window moved to another monitor, and reconfigures the surface.
+    ///
+    /// A new monitor can support other formats or present modes, leaving the current configuration
+    /// suboptimal or invalid. [`GpuManager::handle_window_event`] calls this automatically, call it
+    /// directly when not forwarding window events. Returns whether the configuration changed.
+    ///
+    /// # Errors
+    /// This will error if none of the supported formats can be used, with a [`NoSurfaceFormats`] error
+    /// if the surface reports none.
+    pub fn on_monitor_changed(&mut self) -> Result<bool> {
+        let monitor = self.surface_manager.window.current_monitor();
+        log::debug!(
+            target: LOG_TARGET,
+            "The window moved to monitor {:?}, refreshing Surface capabilities...",
+            monitor.as_ref().and_then(MonitorHandle::name)
+        );
+        self.surface_manager.monitor = monitor;
+        let changed = self.refresh_capabilities()?;
+        if !changed {
+            self.reconfigure();
+        }
+        Ok(changed)
+    }
+
+    /// Queries the [`Surface`] capabilities again and makes sure the current [`SurfaceConfiguration`] is
+    /// still supported.
+    ///
+    /// Some platforms (such as Wayland or Android) can change the capabilities of a surface after its
+    /// creation, for example when the window moves to an HDR monitor. Any part of the configuration that
+    /// is no longer supported is replaced, with a warning, and the surface is reconfigured.
+    ///
+    /// Returns whether the configuration changed. If it did, pipelines targeting the previous format may
+    /// need to be recreated.
+    ///
+    /// # Errors
+    /// This will error if none of the supported formats can be used, with a [`NoSurfaceFormats`] error
+    /// if the surface reports none.
+    pub fn refresh_capabilities(&mut self) -> Result<bool> {
+        let (config, changed) = self.supported_config(&self.adapter)?;
+        if changed {
+            self.surface_manager.config = config;
+            self.reconfigure();
+        }
+        Ok(changed)
+    }
+
+    /// Returns the current [`SurfaceConfiguration`] with the parts the [`Surface`] doesn't support with
+    /// `adapter` replaced, and whether anything was.
+    pub(crate) fn supported_config(
+        &self,
+        adapter: &Adapter,
+    ) -> Result<(SurfaceConfiguration, bool)> {
+        let surface_caps = self.surface_manager.surface.get_capabilities(adapter);
+        log::trace!(target: LOG_TARGET, "Surface capabilities:\n{surface_caps:#?}");
+        let preferred_format = self.surface_manager.preferred_format;
+        let mut config = self.surface_manager.config.clone();
+        let mut changed = false;
+
+        if !surface_caps.formats.contains(&config.format) {
+            let format = Self::get_surface_format(&surface_caps.formats, preferred_format)?;
+            log::warn!(
+                target: LOG_TARGET,
+                "Surface format {:?} is no longer supported, switching to {format:?}.",
+                config.format
+            );
+            config.format = format;
+            config.view_formats.retain(|view_format| {
+                view_format.remove_srgb_suffix() == format.remove_srgb_suffix()
+            });
+            changed = true;
+        }
+        if !surface_caps.present_modes.contains(&config.present_mode) {
+            log::warn!(
+                target: LOG_TARGET,
+                "Present mode {:?} is no longer supported, switching to {:?}.",
+                config.present_mode,
+                PresentMode::Fifo
+            );
+            config.present_mode = PresentMode::Fifo;
+            changed = true;
+        }
+        if !surface_caps.alpha_modes.contains(&config.alpha_mode) {
+            let alpha_mode = surface_caps
+                .alpha_modes
+                .first()
+                .copied()
+                .unwrap_or(CompositeAlphaMode::Auto);
+            log::warn!(
+                target: LOG_TARGET,
+                "Alpha mode {:?} is no longer supported, switching to {alpha_mode:?}.",
+                config.alpha_mode
+            );
+            config.alpha_mode = alpha_mode;
+            changed = true;
+        }
+        if !surface_caps.usages.contains(config.usage) {
+            let usage = config.usage & surface_caps.usages | TextureUsages::RENDER_ATTACHMENT;
+            log::warn!(
+                target: LOG_TARGET,
+                "Surface usages {:?} are no longer supported, switching to {usage:?}.",
+                config.usage
+            );
+            config.usage = usage;
+            changed = true;
+        }
+        Ok((config, changed))
+    }
+
+    /// Returns the [`PresentMode`]s the [`Surface`] supports with the current [`Adapter`].
+    ///
+    /// [`PresentMode::Fifo`] is always supported. This is meant for a graphics settings menu, together
+    /// with [`GpuManager::set_present_mode`].
+    pub fn supported_present_modes(&self) -> Vec<PresentMode> {
+        self.surface_manager
+            .surface
+            .get_capabilities(&self.adapter)
+            .present_modes
+    }
+
+    /// Returns the multisample counts render targets in the surface format support, in increasing
+    /// order, always starting with 1.
+    ///
+    /// This is meant for an anti-aliasing setting. The counts come from
+    /// [`GpuManager::format_features`], so they only go beyond what WebGPU guarantees (1 and 4 for most
+    /// formats) when [`Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] is enabled.
+    pub fn supported_sample_counts(&self) -> Vec<u32> {
+        self.format_features(self.surface_manager.config.format)
+            .flags
+            .supported_sample_counts()
+    }
+
+    /// Reconfigures the [`Surface`] to use the given [`PresentMode`].
+    ///
+    /// # Errors
+    /// This will error if `present_mode` isn't one of the [`GpuManager::supported_present_modes`]. The
+    /// configuration is left untouched in that case.
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) -> Result<()> {
+        let supported = self.supported_present_modes();
+        if !supported.contains(&present_mode) {
+            bail!(
+                "Present mode {present_mode:?} isn't supported by the surface, which supports {supported:?}."
+            );
+        }
+        log::debug!(target: LOG_TARGET, "Using present mode {present_mode:?}.");
+        self.surface_manager.config.present_mode = present_mode;
+        self.reconfigure();
+        Ok(())
+    }
+
+    /// Reconfigures the [`Surface`] to use the given [`Buffering`], choosing the
+    /// [`PresentMode`] and [`desired_maximum_frame_latency`](SurfaceConfiguration::desired_maximum_frame_latency)
+    /// that best match it.
+    ///
+    /// See [`Buffering`] for how each variant is mapped, and how backends interpret it.
+    pub fn set_buffering(&mut self, buffering: Buffering) {
+        let present_modes = self.supported_present_modes();
+        let config = &mut self.surface_manager.config;
+        (config.present_mode, config.desired_maximum_frame_latency) = match buffering {
+            Buffering::Double => (PresentMode::Fifo, 1),
+            Buffering::Triple if present_modes.contains(&PresentMode::Mailbox) => {
+                (PresentMode::Mailbox, 2)
+            }
+            Buffering::Triple => (PresentMode::Fifo, 2),
+        };
+        log::debug!(
+            target: LOG_TARGET,
+            "Using {buffering:?} buffering: {:?} with a frame latency of {}.",
+            config.present_mode,
+            config.desired_maximum_frame_latency
+        );
+        self.reconfigure();
+    }
+
+    /// Reconfigures the [`Surface`] to wait for the vertical blank before presenting or not.
+    ///
+    /// `true` uses [`PresentMode::Fifo`], which caps the framerate to the display's refresh rate.
+    /// `false` uncaps it, for example for benchmarking, with [`PresentMode::Immediate`] (which may tear),
+    /// or [`PresentMode::Mailbox`] with a warning when it isn't supported. If neither is, a warning is
+    /// logged and [`PresentMode::Fifo`] is kept.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        let present_modes = self.supported_present_modes();
+        let present_mode = if vsync {
+            PresentMode::Fifo
+        } else if present_modes.contains(&PresentMode::Immediate) {
+            PresentMode::Immediate
+        } else if present_modes.contains(&PresentMode::Mailbox) {
+            log::warn!(
+                target: LOG_TARGET,
+                "The surface doesn't support {:?}, using {:?}.",
+                PresentMode::Immediate,
+                PresentMode::Mailbox
+            );
+            PresentMode::Mailbox
+        } else {
+            log::warn!(
+                target: LOG_TARGET,
+                "The surface can't present without vsync, using {:?}.",
+                PresentMode::Fifo
+            );
+            PresentMode::Fifo
+        };
+        log::debug!(target: LOG_TARGET, "Using present mode {present_mode:?}.");
+        self.surface_manager.config.present_mode = present_mode;
+        self.reconfigure();
+    }
+
+    /// Returns whether configuring the [`Surface`] was deferred, because the window had a zero width or
+    /// height when the manager was created.
+    ///
+    /// Some platforms (such as Wayland compositors) only give the window a size once it is mapped, and a
+    /// surface can't be configured with an empty size. Until [`GpuManager::resize`] is called with a
+    /// non-zero size, [`GpuManager::acquire_frame`] errors and [`GpuManager::render`] draws nothing.
+    pub fn is_configuration_deferred(&self) -> bool {
+        self.surface_manager.config.width == 0 || self.surface_manager.config.height == 0
+    }
+
     /// Splits this [`GpuManager<WindowManager>`] into a [`GpuManager<()>`] and a [`WindowManager`].
     pub fn split(self) -> (GpuManager<()>, WindowManager<'window>) {
         (
             GpuManager {
                 surface_manager: (),
+                instance: self.instance,
+                adapter: self.adapter,
                 device: self.device,
                 queue: self.queue,
+                blitter: self.blitter,
+                standard_samplers: self.standard_samplers,
+                encoder_pool: self.encoder_pool,
+                profiler: self.profiler,
+                memory_hints: self.memory_hints,
+                strict_validation: self.strict_validation,
+                error_queue: self.error_queue,
+                error_scopes: self.error_scopes,
+                dropped_features: self.dropped_features,
             },
             self.surface_manager,
         )
@@ -161,66 +1178,196 @@ impl<'window> GpuManager<WindowManager<'window>> {
 
     fn create_window(
         event_loop: &winit::event_loop::ActiveEventLoop,
+        attributes: WindowAttributes,
     ) -> Result<Window, winit::error::OsError> {
-        log::trace!("Creating window...");
-        event_loop.create_window(
-            WindowAttributes::default()
-                .with_maximized(true)
-                .with_resizable(false)
-                .with_title("Ray tracer"),
-        )
+        log::trace!(target: LOG_TARGET, "Creating window...");
+        event_loop.create_window(attributes)
     }
 
     fn create_surface_configuration(
         surface: &Surface,
         adapter: &Adapter,
+        device: &Device,
         window: &Window,
+        preferred_format: bool,
     ) -> Result<SurfaceConfiguration> {
-        fn get_surface_format(available_formats: &[TextureFormat]) -> Result<TextureFormat> {
-            let priority_formats = [
-                wgpu::TextureFormat::Rgba8Unorm,
-                wgpu::TextureFormat::Bgra8Unorm,
-            ];
-            for format in priority_formats {
-                if available_formats.contains(&format) {
-                    return Ok(format);
-                }
-            }
-            bail!("Couldn't get supported surface format, exiting.");
-        }
-
         let surface_caps = surface.get_capabilities(adapter);
-        log::trace!("Surface capabilities:\n{surface_caps:#?}");
+        log::trace!(target: LOG_TARGET, "Surface capabilities:\n{surface_caps:#?}");
         let usage = if surface_caps.usages.contains(TextureUsages::COPY_DST) {
             TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_DST
         } else {
-            log::warn!("Surface can't be copy destination. Using compatibility mode.");
+            log::warn!(target: LOG_TARGET, "Surface can't be copy destination. Using compatibility mode.");
             TextureUsages::RENDER_ATTACHMENT
         };
 
-        let surface_format = get_surface_format(&surface_caps.formats)?;
+        let surface_format = Self::get_surface_format(&surface_caps.formats, preferred_format)?;
+        // Fifo and Auto are always supported, but some drivers report no modes at all.
+        let present_mode = surface_caps.present_modes.first().copied().unwrap_or_else(|| {
+            log::warn!(target: LOG_TARGET, "Surface reports no present modes, using {:?}.", PresentMode::Fifo);
+            PresentMode::Fifo
+        });
+        let alpha_mode = surface_caps
+            .alpha_modes
+            .first()
+            .copied()
+            .unwrap_or_else(|| {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "Surface reports no alpha modes, using {:?}.",
+                    CompositeAlphaMode::Auto
+                );
+                CompositeAlphaMode::Auto
+            });
 
-        let size = window.inner_size();
+        let (width, height) = Self::clamp_size(
+            window.inner_size().width,
+            window.inner_size().height,
+            device,
+        );
         Ok(SurfaceConfiguration {
             usage,
             format: surface_format,
-            width: size.width,
-            height: size.height,
-            present_mode: surface_caps.present_modes[0],
+            width,
+            height,
+            present_mode,
             desired_maximum_frame_latency: 2,
-            alpha_mode: surface_caps.alpha_modes[0],
+            alpha_mode,
             view_formats: vec![],
         })
     }
+
+    /// Applies the current [`SurfaceConfiguration`], unless configuration is deferred, and redraws the
+    /// next frame.
+    fn reconfigure(&mut self) {
+        if self.is_configuration_deferred() {
+            log::trace!(target: LOG_TARGET, "The window has no size yet, deferring Surface configuration.");
+            return;
+        }
+        if self.surface_manager.configure_on_first_frame {
+            log::trace!(target: LOG_TARGET, "Deferring Surface configuration until the first frame.");
+        } else {
+            log::trace!(
+                target: LOG_TARGET,
+                "Reconfiguring Surface to {}x{}...",
+                self.surface_manager.config.width,
+                self.surface_manager.config.height
+            );
+            self.surface_manager
+                .surface
+                .configure(&self.device, &self.surface_manager.config);
+        }
+        self.surface_manager.needs_redraw = true;
+        self.surface_manager.scaled_target = None;
+    }
+
+    /// Picks the surface format among `available_formats`, as reported by
+    /// [`Surface::get_capabilities`](wgpu::Surface::get_capabilities).
+    ///
+    /// With `preferred` (see [`GpuManagerBuilder::preferred_surface_format`]), this is the first one,
+    /// which the backend prefers. Otherwise, it is the first supported format of a fixed priority list.
+    /// Errors with [`NoSurfaceFormats`] if `available_formats` is empty.
+    fn get_surface_format(
+        available_formats: &[TextureFormat],
+        preferred: bool,
+    ) -> Result<TextureFormat> {
+        // Adapters that can't present, such as some headless or virtual ones, report no formats.
+        let Some(&first_format) = available_formats.first() else {
+            return Err(NoSurfaceFormats.into());
+        };
+        if preferred {
+            return Ok(first_format);
+        }
+        let priority_formats = [
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureFormat::Bgra8Unorm,
+        ];
+        for format in priority_formats {
+            if available_formats.contains(&format) {
+                return Ok(format);
+            }
+        }
+        bail!(
+            "Couldn't get supported surface format among {available_formats:?}, exiting. Try \
+            `GpuManagerBuilder::preferred_surface_format`."
+        );
+    }
 }
+
+/// The error returned when a [`Surface`] reports no supported formats, which means the adapter can't
+/// present to it. Some headless or virtual adapters do.
+///
+/// Creating or reconfiguring a windowed [`GpuManager`] returns it inside an [`anyhow::Error`], so check
+/// for it with [`downcast_ref`](anyhow::Error::downcast_ref), for example to fall back to headless
+/// rendering.
+#[cfg(feature = "window")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoSurfaceFormats;
+
+#[cfg(feature = "window")]
+impl std::fmt::Display for NoSurfaceFormats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The surface reports no supported formats, the adapter can't present to it.")
+    }
+}
+
+#[cfg(feature = "window")]
+impl std::error::Error for NoSurfaceFormats {}
+
+/// How many swapchain images a windowed [`GpuManager`] should aim for, set through
+/// [`GpuManager::set_buffering`].
+///
+/// wgpu doesn't let the swapchain image count be chosen directly. Instead, it is derived from the
+/// [`PresentMode`] and the [`desired_maximum_frame_latency`](SurfaceConfiguration::desired_maximum_frame_latency),
+/// which each variant sets as follows:
+///
+/// | Variant | Present mode | Frame latency |
+/// |---------|--------------|---------------|
+/// | [`Buffering::Double`] | [`PresentMode::Fifo`] | 1 |
+/// | [`Buffering::Triple`] | [`PresentMode::Mailbox`], or [`PresentMode::Fifo`] when unsupported | 2 |
+///
+/// Backends turn the frame latency into an image count differently:
+/// - Vulkan requests one more image than the frame latency, raised to the surface's minimum image count.
+///   Some drivers always use at least three images.
+/// - DX12 creates one more buffer than the frame latency, and waits on the swapchain's latency object.
+/// - Metal sets its maximum drawable count to 2 or 3 accordingly.
+/// - OpenGL and WebGPU ignore the frame latency, so only the present mode changes.
+#[cfg(feature = "window")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Buffering {
+    /// Lowest latency, with the GPU working on at most one frame while another is displayed.
+    Double,
+    /// Higher throughput, letting the GPU work one more frame ahead.
+    Triple,
+}
+
 #[cfg(feature = "window")]
 /// Manages [`Window`] specific attributes, not needed when drawing to a file, for example.
 pub struct WindowManager<'window> {
     window: Arc<Window>,
     surface: Surface<'window>,
     config: SurfaceConfiguration,
+    /// Whether the window is hidden until the first frame is acquired.
+    show_on_first_frame: bool,
+    /// Whether configuring the surface is deferred until the first frame is acquired.
+    configure_on_first_frame: bool,
+    frame_tracker: Option<FrameTracker>,
+    last_present: Option<std::time::Instant>,
+    frame_stats: FrameStats,
+    /// Whether [`GpuManager::render`] should draw the next frame.
+    needs_redraw: bool,
+    render_scale: f32,
+    /// The intermediate target frames are drawn to when the render scale isn't 1, created lazily.
+    scaled_target: Option<TextureView>,
+    /// Whether the surface format is the backend's preferred one, instead of the priority list.
+    preferred_format: bool,
+    /// The monitor the window was on the last time it was checked.
+    monitor: Option<MonitorHandle>,
+    power_aware_present_mode: Option<PowerAwarePresentMode>,
+    /// Whether the device runs on battery, as last told by the application.
+    on_battery: bool,
 }
 
+#[cfg(feature = "window")]
 impl<'window> WindowManager<'window> {
     /// Returns a reference to the contained [`SurfaceConfiguration`].
     pub fn config(&self) -> &SurfaceConfiguration {
@@ -236,4 +1383,57 @@ impl<'window> WindowManager<'window> {
     pub fn window(&self) -> Arc<Window> {
         self.window.clone()
     }
+
+    /// Returns a reference to the contained [`Window`], without touching its reference count.
+    pub fn window_ref(&self) -> &Window {
+        &self.window
+    }
+}
+
+#[cfg(all(test, feature = "window"))]
+mod tests {
+    use wgpu::TextureFormat;
+
+    use crate::{GpuManager, NoSurfaceFormats, WindowManager};
+
+    fn surface_format(formats: &[TextureFormat], preferred: bool) -> anyhow::Result<TextureFormat> {
+        GpuManager::<WindowManager>::get_surface_format(formats, preferred)
+    }
+
+    #[test]
+    fn surface_format_follows_priority_list() {
+        let formats = [TextureFormat::Bgra8UnormSrgb, TextureFormat::Bgra8Unorm];
+        assert_eq!(
+            surface_format(&formats, false).unwrap(),
+            TextureFormat::Bgra8Unorm
+        );
+    }
+
+    #[test]
+    fn surface_format_uses_preferred_format() {
+        let formats = [TextureFormat::Bgra8UnormSrgb, TextureFormat::Bgra8Unorm];
+        assert_eq!(
+            surface_format(&formats, true).unwrap(),
+            TextureFormat::Bgra8UnormSrgb
+        );
+    }
+
+    #[test]
+    fn surface_format_without_priority_formats_errors() {
+        let formats = [TextureFormat::Rgba8UnormSrgb, TextureFormat::Rgba16Float];
+        let error = surface_format(&formats, false).unwrap_err();
+        assert!(error.downcast_ref::<NoSurfaceFormats>().is_none());
+        assert_eq!(
+            surface_format(&formats, true).unwrap(),
+            TextureFormat::Rgba8UnormSrgb
+        );
+    }
+
+    #[test]
+    fn surface_format_without_formats_is_typed_error() {
+        for preferred in [false, true] {
+            let error = surface_format(&[], preferred).unwrap_err();
+            assert_eq!(error.downcast_ref(), Some(&NoSurfaceFormats));
+        }
+    }
 }
@@ -0,0 +1,58 @@
+//! Reports of the GPU memory allocated by the device.
+
+use wgpu::AllocatorReport;
+
+use crate::GpuManager;
+
+/// A summary of the GPU memory allocated by a [`Device`](wgpu::Device), as returned by
+/// [`GpuManager::memory_report`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// The number of live allocations, sub-allocated from the memory blocks.
+    pub allocation_count: usize,
+    /// The number of memory blocks reserved from the driver.
+    pub block_count: usize,
+    /// The memory used by all allocations, in bytes.
+    pub allocated_bytes: u64,
+    /// The memory reserved by all memory blocks, including their unallocated regions, in bytes.
+    pub reserved_bytes: u64,
+}
+
+impl From<&AllocatorReport> for MemoryReport {
+    fn from(report: &AllocatorReport) -> Self {
+        Self {
+            allocation_count: report.allocations.len(),
+            block_count: report.blocks.len(),
+            allocated_bytes: report.total_allocated_bytes,
+            reserved_bytes: report.total_reserved_bytes,
+        }
+    }
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Returns a summary of the GPU memory currently allocated by the [`Device`](wgpu::Device).
+    ///
+    /// Comparing reports over time helps spotting leaks, and the gap between
+    /// [`MemoryReport::reserved_bytes`] and [`MemoryReport::allocated_bytes`] shows how much reserved
+    /// memory is left unused. For the details of every allocation and block, use
+    /// [`Device::generate_allocator_report`](wgpu::Device::generate_allocator_report) directly.
+    ///
+    /// Returns [`None`] if the backend doesn't sub-allocate memory itself, such as GL, Metal or the web.
+    /// Vulkan and DX12 may also return [`None`] when sub-allocation is disabled, for example to work
+    /// around driver issues.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// if let Some(report) = manager.memory_report() {
+    ///     assert!(report.allocated_bytes <= report.reserved_bytes);
+    /// }
+    /// ```
+    pub fn memory_report(&self) -> Option<MemoryReport> {
+        self.device
+            .generate_allocator_report()
+            .map(|report| MemoryReport::from(&report))
+    }
+}
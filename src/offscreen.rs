@@ -0,0 +1,310 @@
+//! Rendering to textures instead of a window, with optional depth and multisampled attachments.
+
+use anyhow::{Result, bail};
+use wgpu::{
+    Color, Device, Extent3d, Operations, RenderPassColorAttachment, Texture, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+use crate::{GpuManager, LOG_TARGET};
+
+/// The render targets of an offscreen [`GpuManager`], kept at the same size.
+///
+/// The color texture is single sampled and can be read back, sampled or copied from. When a depth
+/// format is set with [`GpuManager::with_depth`], a depth texture is created along with it, and when
+/// multisampling is enabled with [`GpuManager::with_msaa`], rendering happens to a multisampled color
+/// texture which is then resolved to the color texture.
+///
+/// Obtained through [`GpuManager::into_offscreen`].
+#[derive(Debug)]
+pub struct OffscreenManager {
+    format: TextureFormat,
+    depth_format: Option<TextureFormat>,
+    sample_count: u32,
+    color: Texture,
+    color_view: TextureView,
+    msaa_view: Option<TextureView>,
+    depth_view: Option<TextureView>,
+}
+
+impl OffscreenManager {
+    fn new(
+        device: &Device,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        depth_format: Option<TextureFormat>,
+        sample_count: u32,
+    ) -> Self {
+        log::trace!(
+            target: LOG_TARGET,
+            "Creating {width}x{height} offscreen {format:?} targets, with {sample_count} samples and depth {depth_format:?}..."
+        );
+        let create = |label, format, sample_count, usage| {
+            device.create_texture(&TextureDescriptor {
+                label: Some(label),
+                size: Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count,
+                dimension: TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+        };
+        let color = create(
+            "Offscreen color target",
+            format,
+            1,
+            TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC,
+        );
+        let msaa_view = (sample_count > 1).then(|| {
+            create(
+                "Offscreen multisampled target",
+                format,
+                sample_count,
+                TextureUsages::RENDER_ATTACHMENT,
+            )
+            .create_view(&TextureViewDescriptor::default())
+        });
+        let depth_view = depth_format.map(|depth_format| {
+            create(
+                "Offscreen depth target",
+                depth_format,
+                sample_count,
+                TextureUsages::RENDER_ATTACHMENT,
+            )
+            .create_view(&TextureViewDescriptor::default())
+        });
+        Self {
+            format,
+            depth_format,
+            sample_count,
+            color_view: color.create_view(&TextureViewDescriptor::default()),
+            color,
+            msaa_view,
+            depth_view,
+        }
+    }
+
+    /// Returns the single sampled color texture, holding the rendered (and resolved) image.
+    pub fn color_texture(&self) -> &Texture {
+        &self.color
+    }
+
+    /// Returns a view of the single sampled color texture.
+    ///
+    /// Without multisampling, this is the view to render to.
+    pub fn color_view(&self) -> &TextureView {
+        &self.color_view
+    }
+
+    /// Returns a view of the multisampled color texture, if multisampling is enabled.
+    pub fn msaa_view(&self) -> Option<&TextureView> {
+        self.msaa_view.as_ref()
+    }
+
+    /// Returns a view of the depth texture, if a depth format was set.
+    pub fn depth_view(&self) -> Option<&TextureView> {
+        self.depth_view.as_ref()
+    }
+
+    /// Returns the view multisampled rendering must be resolved to, which is the
+    /// [`OffscreenManager::color_view`], or [`None`] without multisampling.
+    pub fn resolve(&self) -> Option<&TextureView> {
+        self.msaa_view.as_ref().map(|_| &self.color_view)
+    }
+
+    /// Returns a color attachment rendering to the right view, and resolving to the color texture when
+    /// multisampling is enabled.
+    pub fn color_attachment(&self, ops: Operations<Color>) -> RenderPassColorAttachment<'_> {
+        RenderPassColorAttachment {
+            view: self.msaa_view.as_ref().unwrap_or(&self.color_view),
+            depth_slice: None,
+            resolve_target: self.resolve(),
+            ops,
+        }
+    }
+
+    /// Returns the format of the color textures.
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /// Returns the format of the depth texture, if one was set.
+    pub fn depth_format(&self) -> Option<TextureFormat> {
+        self.depth_format
+    }
+
+    /// Returns the number of samples of the multisampled and depth textures, 1 without multisampling.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Returns the width and height of every target.
+    pub fn size(&self) -> (u32, u32) {
+        (self.color.width(), self.color.height())
+    }
+}
+
+impl GpuManager<()> {
+    /// Turns this manager into one rendering to a `width` by `height` color texture of the given
+    /// `format`, without depth or multisampling.
+    ///
+    /// This allows testing, or rendering in CI, through the same attachments as a windowed renderer.
+    /// Add a depth texture with [`GpuManager::with_depth`] and multisampling with
+    /// [`GpuManager::with_msaa`].
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::TextureFormat;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let mut manager = manager
+    ///     .into_offscreen(16, 16, TextureFormat::Rgba8Unorm)
+    ///     .unwrap()
+    ///     .with_depth(TextureFormat::Depth32Float)
+    ///     .unwrap()
+    ///     .with_msaa(4)
+    ///     .unwrap();
+    /// manager.resize(32, 32);
+    ///
+    /// let targets = manager.surface_manager();
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// targets
+    ///     .render_pass(wgpu::Color::WHITE)
+    ///     .begin(&mut encoder);
+    /// manager.queue().submit([encoder.finish()]);
+    ///
+    /// let pixels = manager.read_texture(targets.color_texture()).unwrap();
+    /// assert!(pixels.iter().all(|&channel| channel == 255));
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if `format` can't be rendered to.
+    pub fn into_offscreen(
+        self,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+    ) -> Result<GpuManager<OffscreenManager>> {
+        let allowed_usages = self.format_features(format).allowed_usages;
+        if !allowed_usages.contains(TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC) {
+            bail!("Texture format {format:?} can't be used as an offscreen render target.");
+        }
+        let offscreen =
+            OffscreenManager::new(&self.device, width.max(1), height.max(1), format, None, 1);
+        Ok(GpuManager {
+            surface_manager: offscreen,
+            instance: self.instance,
+            adapter: self.adapter,
+            device: self.device,
+            queue: self.queue,
+            blitter: self.blitter,
+            standard_samplers: self.standard_samplers,
+            encoder_pool: self.encoder_pool,
+            profiler: self.profiler,
+            memory_hints: self.memory_hints,
+            strict_validation: self.strict_validation,
+            error_queue: self.error_queue,
+            error_scopes: self.error_scopes,
+            dropped_features: self.dropped_features,
+        })
+    }
+}
+
+impl GpuManager<OffscreenManager> {
+    /// Returns a reference to the [`OffscreenManager`] holding the render targets.
+    pub fn surface_manager(&self) -> &OffscreenManager {
+        &self.surface_manager
+    }
+
+    /// Adds a depth texture of the given `format`, with the same size and sample count as the color
+    /// targets, replacing the current one if any.
+    ///
+    /// # Errors
+    /// This will error if `format` isn't a depth format that can be rendered to with the current sample
+    /// count.
+    pub fn with_depth(mut self, format: TextureFormat) -> Result<Self> {
+        if !format.is_depth_stencil_format() {
+            bail!("Texture format {format:?} isn't a depth format.");
+        }
+        let features = self.format_features(format);
+        if !features
+            .allowed_usages
+            .contains(TextureUsages::RENDER_ATTACHMENT)
+        {
+            bail!("Texture format {format:?} can't be used as a depth attachment.");
+        }
+        let sample_count = self.surface_manager.sample_count;
+        if !features.flags.sample_count_supported(sample_count) {
+            bail!("Texture format {format:?} doesn't support {sample_count} samples.");
+        }
+        self.surface_manager.depth_format = Some(format);
+        self.recreate_targets();
+        Ok(self)
+    }
+
+    /// Renders to multisampled targets with `sample_count` samples, resolved to the color texture. A
+    /// count of 1 disables multisampling.
+    ///
+    /// The depth texture, if any, is recreated with the same sample count.
+    ///
+    /// # Errors
+    /// This will error if the color or depth format doesn't support `sample_count` samples.
+    pub fn with_msaa(mut self, sample_count: u32) -> Result<Self> {
+        let formats = [
+            Some(self.surface_manager.format),
+            self.surface_manager.depth_format,
+        ];
+        for format in formats.into_iter().flatten() {
+            let supported = self.format_features(format).flags.supported_sample_counts();
+            if !supported.contains(&sample_count) {
+                bail!(
+                    "Texture format {format:?} doesn't support {sample_count} samples. Supported counts are {supported:?}."
+                );
+            }
+        }
+        self.surface_manager.sample_count = sample_count;
+        self.recreate_targets();
+        Ok(self)
+    }
+
+    /// Recreates every target with the new size.
+    ///
+    /// As for windowed managers, the size is clamped to the device's
+    /// [`max_texture_dimension_2d`](wgpu::Limits::max_texture_dimension_2d) with a warning, and sizes
+    /// with a zero width or height are ignored, since textures can't be empty.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let Some((width, height)) = self.resized_size(width, height) else {
+            return;
+        };
+        if self.surface_manager.size() != (width, height) {
+            self.recreate_targets_with_size(width, height);
+        }
+    }
+
+    fn recreate_targets(&mut self) {
+        let (width, height) = self.surface_manager.size();
+        self.recreate_targets_with_size(width, height);
+    }
+
+    fn recreate_targets_with_size(&mut self, width: u32, height: u32) {
+        let offscreen = &self.surface_manager;
+        self.surface_manager = OffscreenManager::new(
+            &self.device,
+            width,
+            height,
+            offscreen.format,
+            offscreen.depth_format,
+            offscreen.sample_count,
+        );
+    }
+}
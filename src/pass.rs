@@ -0,0 +1,143 @@
+//! A builder to begin render passes without spelling out every attachment.
+
+use wgpu::{
+    Color, CommandEncoder, LoadOp, Operations, RenderPass, RenderPassColorAttachment,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor, StoreOp, TextureView,
+};
+
+use crate::OffscreenManager;
+
+/// Collects the attachments of a render pass, then begins it on an encoder.
+///
+/// Color attachments are bound in the order they are added. Attachments added through the shorthand
+/// methods are always stored. Use [`RenderPassBuilder::color`] and [`RenderPassBuilder::depth`] for
+/// full control over an attachment, such as discarding it or resolving it.
+///
+/// Obtained through [`RenderPassBuilder::new`], or [`OffscreenManager::render_pass`] to target the
+/// offscreen attachments.
+///
+/// # Examples
+/// Clearing a texture and reading it back:
+/// ```
+/// use gpu_manager::{GpuManager, RenderPassBuilder};
+///
+/// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+/// let texture = manager.device().create_texture(&wgpu::TextureDescriptor {
+///     label: None,
+///     size: wgpu::Extent3d {
+///         width: 4,
+///         height: 4,
+///         depth_or_array_layers: 1,
+///     },
+///     mip_level_count: 1,
+///     sample_count: 1,
+///     dimension: wgpu::TextureDimension::D2,
+///     format: wgpu::TextureFormat::Rgba8Unorm,
+///     usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+///     view_formats: &[],
+/// });
+/// let view = texture.create_view(&Default::default());
+///
+/// let mut encoder = manager.device().create_command_encoder(&Default::default());
+/// RenderPassBuilder::new()
+///     .label("Clear pass")
+///     .clear_color(&view, wgpu::Color::RED)
+///     .begin(&mut encoder);
+/// manager.queue().submit([encoder.finish()]);
+///
+/// let pixels = manager.read_texture(&texture).unwrap();
+/// assert!(pixels.chunks_exact(4).all(|pixel| pixel == [255, 0, 0, 255]));
+/// ```
+#[derive(Debug, Default)]
+pub struct RenderPassBuilder<'a> {
+    label: Option<&'a str>,
+    color_attachments: Vec<Option<RenderPassColorAttachment<'a>>>,
+    depth_stencil_attachment: Option<RenderPassDepthStencilAttachment<'a>>,
+}
+
+impl<'a> RenderPassBuilder<'a> {
+    /// Creates a builder for a render pass without any attachment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the debug label of the render pass.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Adds a color attachment rendering to `view`, cleared to `color` first.
+    pub fn clear_color(self, view: &'a TextureView, color: Color) -> Self {
+        self.color_with_ops(view, LoadOp::Clear(color))
+    }
+
+    /// Adds a color attachment rendering to `view`, keeping its previous contents.
+    pub fn load_color(self, view: &'a TextureView) -> Self {
+        self.color_with_ops(view, LoadOp::Load)
+    }
+
+    fn color_with_ops(self, view: &'a TextureView, load: LoadOp<Color>) -> Self {
+        self.color(RenderPassColorAttachment {
+            view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: Operations {
+                load,
+                store: StoreOp::Store,
+            },
+        })
+    }
+
+    /// Adds the given color attachment.
+    pub fn color(mut self, attachment: RenderPassColorAttachment<'a>) -> Self {
+        self.color_attachments.push(Some(attachment));
+        self
+    }
+
+    /// Sets the depth attachment to `view`, cleared to `depth` first. This doesn't touch the stencil
+    /// aspect.
+    pub fn clear_depth(self, view: &'a TextureView, depth: f32) -> Self {
+        self.depth(RenderPassDepthStencilAttachment {
+            view,
+            depth_ops: Some(Operations {
+                load: LoadOp::Clear(depth),
+                store: StoreOp::Store,
+            }),
+            stencil_ops: None,
+        })
+    }
+
+    /// Sets the given depth and stencil attachment, replacing the previous one.
+    pub fn depth(mut self, attachment: RenderPassDepthStencilAttachment<'a>) -> Self {
+        self.depth_stencil_attachment = Some(attachment);
+        self
+    }
+
+    /// Begins the render pass on `encoder`.
+    pub fn begin<'encoder>(self, encoder: &'encoder mut CommandEncoder) -> RenderPass<'encoder> {
+        encoder.begin_render_pass(&RenderPassDescriptor {
+            label: self.label,
+            color_attachments: &self.color_attachments,
+            depth_stencil_attachment: self.depth_stencil_attachment,
+            ..Default::default()
+        })
+    }
+}
+
+impl OffscreenManager {
+    /// Returns a [`RenderPassBuilder`] clearing the color targets to `color`, resolving them when
+    /// multisampling is enabled, and clearing the depth target (if any) to 1.
+    ///
+    /// More color attachments can be added to the builder before beginning the pass.
+    pub fn render_pass(&self, color: Color) -> RenderPassBuilder<'_> {
+        let builder = RenderPassBuilder::new().color(self.color_attachment(Operations {
+            load: LoadOp::Clear(color),
+            store: StoreOp::Store,
+        }));
+        match self.depth_view() {
+            Some(depth_view) => builder.clear_depth(depth_view, 1.0),
+            None => builder,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Creating pipelines ahead of their first use, on a background thread.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow};
+use wgpu::{Device, ErrorFilter};
+
+use crate::executor::block_on;
+use crate::submit::{Completed, Completion};
+use crate::{GpuManager, LOG_TARGET};
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Creates pipelines on a background thread, so that their shaders are compiled before they are
+    /// first needed instead of causing a hitch then.
+    ///
+    /// Each entry of `pipelines` pairs a name with a function creating the pipeline (render, compute,
+    /// or anything else) from the [`Device`], for example through a
+    /// [`PipelineCache`](wgpu::PipelineCache). They are created in order, and `progress` is called on the
+    /// background thread after each one, with the name, the number of pipelines created so far and the
+    /// total. The returned future resolves to the result of each entry, in the same order.
+    ///
+    /// Creation errors are caught by pushing [error scopes](wgpu::Device::push_error_scope) around each
+    /// pipeline. In wgpu, these scopes are shared by every thread using the device, so the background
+    /// thread takes turns with the scopes the manager pushes itself:
+    /// [`GpuManager::capture_errors`], and the checks of [`GpuManager::set_strict_validation`], such as
+    /// those of each frame of `GpuManager::render`. These block while a pipeline is being created, and
+    /// pipelines wait for them to finish, so each error is reported by the scope it belongs to.
+    ///
+    /// Other work on the device during precompilation, outside of the manager's scopes, isn't
+    /// serialized: an error it raises while a pipeline is being created is reported as a failure of
+    /// that pipeline, instead of reaching the uncaptured error handler. Record the loading screen with
+    /// strict validation enabled, or inside [`GpuManager::capture_errors`], to keep its errors apart.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let create = |source: &'static str| {
+    ///     move |device: &wgpu::Device| {
+    ///         let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+    ///             label: None,
+    ///             source: wgpu::ShaderSource::Wgsl(source.into()),
+    ///         });
+    ///         device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+    ///             label: None,
+    ///             layout: None,
+    ///             module: &module,
+    ///             entry_point: None,
+    ///             compilation_options: Default::default(),
+    ///             cache: None,
+    ///         })
+    ///     }
+    /// };
+    /// let pipelines = vec![
+    ///     ("Valid".to_string(), create("@compute @workgroup_size(1) fn main() {}")),
+    ///     ("Invalid".to_string(), create("@compute fn main() {}")),
+    /// ];
+    /// let results = pollster::block_on(manager.precompile(pipelines, |name, done, total| {
+    ///     println!("Compiled {name} ({done}/{total})");
+    /// }));
+    /// assert!(results[0].1.is_ok());
+    /// assert!(results[1].1.is_err());
+    /// ```
+    pub fn precompile<P, F>(
+        &self,
+        pipelines: Vec<(String, F)>,
+        mut progress: impl FnMut(&str, usize, usize) + Send + 'static,
+    ) -> impl Future<Output = Vec<(String, Result<P>)>> + Send + 'static
+    where
+        P: Send + 'static,
+        F: FnOnce(&Device) -> P + Send + 'static,
+    {
+        let completion = Arc::new(Mutex::new(Completion::default()));
+        let on_done = completion.clone();
+        let device = self.device.clone();
+        let error_scopes = self.error_scopes.clone();
+        std::thread::spawn(move || {
+            let total = pipelines.len();
+            let mut results = Vec::with_capacity(total);
+            for (done, (name, create)) in pipelines.into_iter().enumerate() {
+                log::trace!(target: LOG_TARGET, "Precompiling pipeline {name}...");
+                let guard = error_scopes.lock();
+                device.push_error_scope(ErrorFilter::Internal);
+                device.push_error_scope(ErrorFilter::Validation);
+                let pipeline = create(&device);
+                let validation = block_on(device.pop_error_scope());
+                let internal = block_on(device.pop_error_scope());
+                drop(guard);
+                let result = match validation.or(internal) {
+                    Some(error) => {
+                        log::warn!(target: LOG_TARGET, "Couldn't precompile pipeline {name}: {error}");
+                        Err(anyhow!("Couldn't create pipeline {name}: {error}"))
+                    }
+                    None => Ok(pipeline),
+                };
+                progress(&name, done + 1, total);
+                results.push((name, result));
+            }
+            Completion::complete(&on_done, results);
+        });
+        Completed { completion }
+    }
+}
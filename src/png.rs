@@ -0,0 +1,102 @@
+//! A minimal PNG encoder for 8 bit RGBA images.
+//!
+//! The image data is stored without compression, which keeps the encoder small and dependency free at
+//! the cost of bigger files.
+
+use anyhow::{Result, bail};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/// The biggest amount of data a single stored deflate block can hold.
+const MAX_STORED_BLOCK: usize = u16::MAX as usize;
+
+/// Encodes a `width` by `height` image of tightly packed 8 bit RGBA `pixels` as a PNG file.
+pub(crate) fn encode_rgba8(width: u32, height: u32, pixels: &[u8]) -> Result<Vec<u8>> {
+    let row_len = width as usize * 4;
+    if pixels.len() != row_len * height as usize {
+        bail!(
+            "A {width}x{height} RGBA image needs {} bytes, but {} were given.",
+            row_len * height as usize,
+            pixels.len()
+        );
+    }
+
+    // Each row starts with its filter type, which is always `None` here.
+    let mut scanlines = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in pixels.chunks_exact(row_len.max(1)) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, deflate compression, adaptive filtering, no interlacing.
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = Vec::with_capacity(scanlines.len() + scanlines.len() / MAX_STORED_BLOCK * 5 + 64);
+    png.extend_from_slice(&SIGNATURE);
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = Vec::with_capacity(data.len() + data.len() / MAX_STORED_BLOCK * 5 + 11);
+    // Deflate with a 32K window, and the check bits making the header a multiple of 31.
+    stream.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        stream.push(u8::from(is_last));
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULO: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the most bytes that can be summed before `b` may overflow.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MODULO;
+        b %= MODULO;
+    }
+    (b << 16) | a
+}
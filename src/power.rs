@@ -0,0 +1,109 @@
+//! Switching present modes depending on whether the device runs on battery.
+//!
+//! Only available when the `window` feature is activated.
+
+use wgpu::PresentMode;
+
+use crate::{GpuManager, LOG_TARGET, WindowManager};
+
+/// The present modes a windowed [`GpuManager`] switches between when told whether the device runs on
+/// battery through [`GpuManager::set_on_battery`]. Enable it with
+/// [`GpuManager::set_power_aware_present_mode`].
+///
+/// The default mapping saves power on battery and lowers latency on AC:
+///
+/// | Power source | Present mode |
+/// |--------------|--------------|
+/// | Battery | [`PresentMode::Fifo`], which caps the framerate to the display's refresh rate |
+/// | AC | [`PresentMode::Mailbox`], which keeps rendering and presents the latest frame |
+///
+/// A mode the surface doesn't support is replaced by [`PresentMode::Fifo`], which is always supported,
+/// with a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerAwarePresentMode {
+    /// The present mode used while the device runs on battery.
+    pub on_battery: PresentMode,
+    /// The present mode used while the device is plugged in.
+    pub on_ac: PresentMode,
+}
+
+impl Default for PowerAwarePresentMode {
+    fn default() -> Self {
+        Self {
+            on_battery: PresentMode::Fifo,
+            on_ac: PresentMode::Mailbox,
+        }
+    }
+}
+
+impl PowerAwarePresentMode {
+    /// Returns the present mode to use for the given power source.
+    pub fn present_mode(self, on_battery: bool) -> PresentMode {
+        if on_battery {
+            self.on_battery
+        } else {
+            self.on_ac
+        }
+    }
+}
+
+impl GpuManager<WindowManager<'_>> {
+    /// Enables switching present modes when the power source changes, or disables it with [`None`].
+    ///
+    /// The crate doesn't detect the power source itself: pass each change to
+    /// [`GpuManager::set_on_battery`], for example from the platform's power notifications.
+    /// Enabling applies the mode for the last known power source right away, which is AC until told
+    /// otherwise. Disabling keeps the current present mode.
+    pub fn set_power_aware_present_mode(&mut self, modes: Option<PowerAwarePresentMode>) {
+        log::debug!(target: LOG_TARGET, "Setting power aware present modes to {modes:?}.");
+        self.surface_manager.power_aware_present_mode = modes;
+        self.apply_power_aware_present_mode();
+    }
+
+    /// Returns the present modes set through [`GpuManager::set_power_aware_present_mode`], if enabled.
+    pub fn power_aware_present_mode(&self) -> Option<PowerAwarePresentMode> {
+        self.surface_manager.power_aware_present_mode
+    }
+
+    /// Tells the manager whether the device runs on battery, reconfiguring the
+    /// [`Surface`](wgpu::Surface) with the matching present mode when
+    /// [`GpuManager::set_power_aware_present_mode`] is enabled.
+    ///
+    /// Nothing is reconfigured if the present mode doesn't change, so this can be called on every power
+    /// state update.
+    pub fn set_on_battery(&mut self, on_battery: bool) {
+        self.surface_manager.on_battery = on_battery;
+        self.apply_power_aware_present_mode();
+    }
+
+    /// Returns whether the device runs on battery, as last told through [`GpuManager::set_on_battery`].
+    pub fn is_on_battery(&self) -> bool {
+        self.surface_manager.on_battery
+    }
+
+    /// Reconfigures the surface with the power aware present mode, if enabled and different.
+    fn apply_power_aware_present_mode(&mut self) {
+        let Some(modes) = self.surface_manager.power_aware_present_mode else {
+            return;
+        };
+        let on_battery = self.surface_manager.on_battery;
+        let mut present_mode = modes.present_mode(on_battery);
+        if !self.supported_present_modes().contains(&present_mode) {
+            log::warn!(
+                target: LOG_TARGET,
+                "The surface doesn't support {present_mode:?}, using {:?} instead.",
+                PresentMode::Fifo
+            );
+            present_mode = PresentMode::Fifo;
+        }
+        if present_mode != self.surface_manager.config.present_mode {
+            log::debug!(
+                target: LOG_TARGET,
+                "Using present mode {present_mode:?} on {}.",
+                if on_battery { "battery" } else { "AC" }
+            );
+            self.surface_manager.config.present_mode = present_mode;
+            self.reconfigure();
+        }
+    }
+}
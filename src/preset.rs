@@ -0,0 +1,103 @@
+//! Bundles of options trading visual quality for speed or power usage.
+
+use wgpu::MemoryHints;
+#[cfg(feature = "window")]
+use wgpu::PresentMode;
+
+#[cfg(feature = "window")]
+use crate::{GpuManager, LOG_TARGET, WindowManager};
+
+/// A set of options to tune a [`GpuManager`](crate::GpuManager) with a single choice, applied through
+/// [`GpuManagerBuilder::preset`](crate::GpuManagerBuilder::preset), or at runtime on windowed managers
+/// through [`GpuManager::set_preset`](crate::GpuManager::set_preset).
+///
+/// Each preset sets the following:
+///
+/// | Preset | Present mode | Frame latency | Render scale | Memory hints |
+/// |--------|--------------|---------------|--------------|--------------|
+/// | [`Preset::Quality`] | `Fifo` | 3 | 1.5 | [`MemoryHints::Performance`] |
+/// | [`Preset::Balanced`] | `Fifo` | 2 | 1 | [`MemoryHints::Performance`] |
+/// | [`Preset::Performance`] | `Immediate`, else `Mailbox`, else `Fifo` | 1 | 0.75 | [`MemoryHints::Performance`] |
+/// | [`Preset::PowerSaver`] | `Fifo` | 1 | 0.5 | [`MemoryHints::MemoryUsage`] |
+///
+/// The present mode, frame latency and render scale only apply to windowed managers (see
+/// [`GpuManager::set_render_scale`](crate::GpuManager::set_render_scale) for the render scale). The
+/// memory hints are only used when the [`Device`](wgpu::Device) is created, so
+/// [`GpuManager::set_preset`](crate::GpuManager::set_preset) leaves them untouched.
+///
+/// # Examples
+/// ```
+/// use gpu_manager::{GpuManager, Preset};
+///
+/// let builder = GpuManager::builder().preset(Preset::PowerSaver);
+/// let manager = pollster::block_on(builder.build()).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// Supersamples frames and keeps a deeper swapchain queue for smoother pacing.
+    Quality,
+    /// Renders at the native resolution with vsync.
+    #[default]
+    Balanced,
+    /// Uncaps the framerate and lowers the resolution and latency.
+    Performance,
+    /// Caps the framerate and halves the resolution, favouring less memory over speed.
+    PowerSaver,
+}
+
+impl Preset {
+    pub(crate) fn memory_hints(self) -> MemoryHints {
+        match self {
+            Self::Quality | Self::Balanced | Self::Performance => MemoryHints::Performance,
+            Self::PowerSaver => MemoryHints::MemoryUsage,
+        }
+    }
+
+    #[cfg(feature = "window")]
+    pub(crate) fn frame_latency(self) -> u32 {
+        match self {
+            Self::Quality => 3,
+            Self::Balanced => 2,
+            Self::Performance | Self::PowerSaver => 1,
+        }
+    }
+
+    #[cfg(feature = "window")]
+    pub(crate) fn render_scale(self) -> f32 {
+        match self {
+            Self::Quality => 1.5,
+            Self::Balanced => 1.0,
+            Self::Performance => 0.75,
+            Self::PowerSaver => 0.5,
+        }
+    }
+
+    /// Returns the present mode of this preset, among the `supported` ones.
+    #[cfg(feature = "window")]
+    pub(crate) fn present_mode(self, supported: &[PresentMode]) -> PresentMode {
+        match self {
+            Self::Performance => [PresentMode::Immediate, PresentMode::Mailbox]
+                .into_iter()
+                .find(|present_mode| supported.contains(present_mode))
+                .unwrap_or(PresentMode::Fifo),
+            Self::Quality | Self::Balanced | Self::PowerSaver => PresentMode::Fifo,
+        }
+    }
+}
+
+#[cfg(feature = "window")]
+impl GpuManager<WindowManager<'_>> {
+    /// Applies the present mode, frame latency and render scale of `preset`, and reconfigures the
+    /// [`Surface`](wgpu::Surface).
+    ///
+    /// See [`Preset`] for what each preset sets.
+    pub fn set_preset(&mut self, preset: Preset) {
+        log::debug!(target: LOG_TARGET, "Applying the {preset:?} preset.");
+        let present_modes = self.supported_present_modes();
+        let config = &mut self.surface_manager.config;
+        config.present_mode = preset.present_mode(&present_modes);
+        config.desired_maximum_frame_latency = preset.frame_latency();
+        self.set_render_scale(preset.render_scale());
+        self.reconfigure();
+    }
+}
@@ -0,0 +1,243 @@
+//! Measuring how long parts of a command encoder take on the GPU, with timestamp queries.
+
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result, bail};
+use wgpu::{
+    Buffer, BufferDescriptor, BufferUsages, CommandEncoder, CommandEncoderDescriptor, Device,
+    Features, MapMode, PollType, QUERY_SIZE, QuerySet, QuerySetDescriptor, QueryType,
+};
+
+use crate::{GpuManager, LOG_TARGET};
+
+/// How many timestamps can be written between two calls to [`GpuManager::profile_results`].
+const MAX_QUERIES: u32 = 512;
+
+/// The features requested by [`GpuManagerBuilder::timestamp_queries`](crate::GpuManagerBuilder::timestamp_queries).
+pub(crate) const TIMESTAMP_FEATURES: Features =
+    Features::TIMESTAMP_QUERY.union(Features::TIMESTAMP_QUERY_INSIDE_ENCODERS);
+
+/// The lazily created state needed by [`GpuManager::gpu_scope`].
+#[derive(Debug, Default)]
+pub(crate) struct Profiler {
+    resources: OnceLock<ProfilerResources>,
+    state: Mutex<ProfilerState>,
+}
+
+#[derive(Debug)]
+struct ProfilerResources {
+    query_set: QuerySet,
+    /// Where the timestamps are resolved to, before being copied to `readback`.
+    resolve: Buffer,
+    readback: Buffer,
+}
+
+#[derive(Debug, Default)]
+struct ProfilerState {
+    /// The name and first query of every scope created since the last readback.
+    scopes: Vec<(String, u32)>,
+    /// The accumulated duration of every scope name, in milliseconds.
+    totals: HashMap<String, f32>,
+}
+
+impl ProfilerResources {
+    fn new(device: &Device) -> Self {
+        log::trace!(target: LOG_TARGET, "Creating profiler resources...");
+        let size = u64::from(MAX_QUERIES) * u64::from(QUERY_SIZE);
+        Self {
+            query_set: device.create_query_set(&QuerySetDescriptor {
+                label: Some("Profiler query set"),
+                ty: QueryType::Timestamp,
+                count: MAX_QUERIES,
+            }),
+            resolve: device.create_buffer(&BufferDescriptor {
+                label: Some("Profiler resolve buffer"),
+                size,
+                usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback: device.create_buffer(&BufferDescriptor {
+                label: Some("Profiler readback buffer"),
+                size,
+                usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+        }
+    }
+}
+
+/// Measures the GPU time taken by the work recorded into an encoder while it is alive.
+///
+/// A timestamp is written when the scope is created, and another one when it is dropped. The scope
+/// dereferences to the [`CommandEncoder`] it was created for, so passes can be recorded through it.
+///
+/// The elapsed time is only known once the encoder was submitted and the timestamps read back, which
+/// [`GpuManager::profile_results`] does.
+///
+/// Obtained through [`GpuManager::gpu_scope`].
+#[derive(Debug)]
+pub struct GpuScope<'a> {
+    encoder: &'a mut CommandEncoder,
+    query_set: &'a QuerySet,
+    end_query: u32,
+}
+
+impl Deref for GpuScope<'_> {
+    type Target = CommandEncoder;
+
+    fn deref(&self) -> &Self::Target {
+        self.encoder
+    }
+}
+
+impl DerefMut for GpuScope<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.encoder
+    }
+}
+
+impl Drop for GpuScope<'_> {
+    fn drop(&mut self) {
+        self.encoder.write_timestamp(self.query_set, self.end_query);
+    }
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Starts measuring the GPU time of the work recorded into `encoder`, until the returned
+    /// [`GpuScope`] is dropped.
+    ///
+    /// The measured time is added to the total of `name` by the next call to
+    /// [`GpuManager::profile_results`], after `encoder` was submitted. Scopes can be nested, in which
+    /// case the outer scope includes the time of the inner ones.
+    ///
+    /// Requires the features requested by
+    /// [`GpuManagerBuilder::timestamp_queries`](crate::GpuManagerBuilder::timestamp_queries).
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let Ok(manager) = pollster::block_on(GpuManager::builder().timestamp_queries(true).build())
+    /// else {
+    ///     // Timestamp queries aren't supported by this adapter.
+    ///     return;
+    /// };
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// {
+    ///     let mut scope = manager.gpu_scope(&mut encoder, "Simulation").unwrap();
+    ///     scope.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+    /// }
+    /// manager.queue().submit([encoder.finish()]);
+    ///
+    /// let results = manager.profile_results().unwrap();
+    /// assert!(results["Simulation"] >= 0.0);
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) the [`Device`] doesn't support timestamp queries, or 2) too many scopes
+    /// were created since the last call to [`GpuManager::profile_results`].
+    pub fn gpu_scope<'a>(
+        &'a self,
+        encoder: &'a mut CommandEncoder,
+        name: impl Into<String>,
+    ) -> Result<GpuScope<'a>> {
+        if !self.device.features().contains(TIMESTAMP_FEATURES) {
+            bail!(
+                "Timestamp queries aren't enabled on the device. Request them with `GpuManagerBuilder::timestamp_queries`."
+            );
+        }
+        let resources = self
+            .profiler
+            .resources
+            .get_or_init(|| ProfilerResources::new(&self.device));
+
+        let mut state = self
+            .profiler
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let start_query = state.scopes.len() as u32 * 2;
+        if start_query >= MAX_QUERIES {
+            bail!(
+                "Only {} GPU scopes can be measured at once. Call `GpuManager::profile_results` to read them back.",
+                MAX_QUERIES / 2
+            );
+        }
+        state.scopes.push((name.into(), start_query));
+        drop(state);
+
+        encoder.write_timestamp(&resources.query_set, start_query);
+        Ok(GpuScope {
+            encoder,
+            query_set: &resources.query_set,
+            end_query: start_query + 1,
+        })
+    }
+
+    /// Reads back the timestamps of every [`GpuScope`] created since the last call, and returns the
+    /// accumulated GPU time of each scope name, in milliseconds.
+    ///
+    /// Every encoder a scope was created for must have been submitted before calling this. This blocks
+    /// until the GPU has finished them.
+    ///
+    /// # Errors
+    /// This will error if mapping the readback buffer or polling the [`Device`] fails.
+    #[track_caller]
+    pub fn profile_results(&self) -> Result<HashMap<String, f32>> {
+        let mut state = self
+            .profiler
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(resources) = self.profiler.resources.get() else {
+            return Ok(state.totals.clone());
+        };
+        if state.scopes.is_empty() {
+            return Ok(state.totals.clone());
+        }
+
+        let query_count = state.scopes.len() as u32 * 2;
+        let size = u64::from(query_count) * u64::from(QUERY_SIZE);
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Profiler readback encoder"),
+            });
+        encoder.resolve_query_set(&resources.query_set, 0..query_count, &resources.resolve, 0);
+        encoder.copy_buffer_to_buffer(&resources.resolve, 0, &resources.readback, 0, size);
+        let submission = self.validate("Submitting the profiler readback", || {
+            self.queue.submit([encoder.finish()])
+        });
+
+        let slice = resources.readback.slice(..size);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device
+            .poll(PollType::WaitForSubmissionIndex(submission))?;
+        receiver
+            .recv()
+            .context("The profiler readback buffer was never mapped.")??;
+
+        let period = self.queue.get_timestamp_period();
+        let mapped = slice.get_mapped_range();
+        let timestamps: Vec<u64> = mapped
+            .chunks_exact(QUERY_SIZE as usize)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("queries are 8 bytes")))
+            .collect();
+        drop(mapped);
+        resources.readback.unmap();
+
+        let ProfilerState { scopes, totals } = &mut *state;
+        for (name, start_query) in scopes.drain(..) {
+            let start = timestamps[start_query as usize];
+            let end = timestamps[start_query as usize + 1];
+            let milliseconds = end.saturating_sub(start) as f32 * period / 1_000_000.0;
+            *totals.entry(name).or_default() += milliseconds;
+        }
+        Ok(totals.clone())
+    }
+}
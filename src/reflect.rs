@@ -0,0 +1,266 @@
+//! Deriving bind group layouts from the bindings a WGSL shader declares.
+//!
+//! Only available when the `naga` feature is activated.
+
+use std::num::{NonZeroU32, NonZeroU64};
+
+use anyhow::{Result, anyhow, bail};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use naga::{
+    AddressSpace, ArraySize, ImageClass, ImageDimension, Module, ScalarKind, ShaderStage,
+    StorageAccess, StorageFormat, TypeInner,
+};
+use wgpu::{
+    BindGroupLayoutEntry, BindingType, BufferBindingType, SamplerBindingType, ShaderStages,
+    StorageTextureAccess, TextureFormat, TextureSampleType, TextureViewDimension,
+};
+
+use crate::{GpuManager, LOG_TARGET};
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Parses `wgsl_source` and derives the entries of the bind group layouts its bindings need, indexed
+    /// by group. Groups the shader doesn't use are left empty.
+    ///
+    /// Each entry is visible to the stages of the entry points using it, and entries are sorted by
+    /// binding. Entries are returned rather than [`BindGroupLayoutDescriptor`](wgpu::BindGroupLayoutDescriptor)s,
+    /// which borrow them, so pass each group to
+    /// [`Device::create_bind_group_layout`](wgpu::Device::create_bind_group_layout).
+    ///
+    /// The shader doesn't tell everything about the bindings, so the following defaults are used:
+    /// - float textures are filterable (unless they are multisampled), and samplers filtering (unless
+    ///   they are comparison samplers);
+    /// - buffers don't have dynamic offsets;
+    /// - uniform buffers require their whole type to be bound, while storage buffers have no minimum
+    ///   size.
+    ///
+    /// Adjust the entries if a binding differs, such as a `Float32` texture sampled without
+    /// [`Features::FLOAT32_FILTERABLE`](wgpu::Features::FLOAT32_FILTERABLE).
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let groups = manager
+    ///     .reflect_layout(
+    ///         "@group(0) @binding(0) var<storage, read> input: array<f32>;
+    ///         @group(0) @binding(1) var<storage, read_write> output: array<f32>;
+    ///
+    ///         @compute @workgroup_size(64)
+    ///         fn main(@builtin(global_invocation_id) id: vec3u) {
+    ///             output[id.x] = input[id.x] * 2.0;
+    ///         }",
+    ///     )
+    ///     .unwrap();
+    /// let layout = manager
+    ///     .device()
+    ///     .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+    ///         label: None,
+    ///         entries: &groups[0],
+    ///     });
+    /// assert!(matches!(
+    ///     groups[0][1].ty,
+    ///     wgpu::BindingType::Buffer {
+    ///         ty: wgpu::BufferBindingType::Storage { read_only: false },
+    ///         ..
+    ///     }
+    /// ));
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) `wgsl_source` isn't valid WGSL, in which case the error points at the
+    /// offending code, or 2) a binding has a type that can't be bound, such as push constants or
+    /// acceleration structures.
+    pub fn reflect_layout(&self, wgsl_source: &str) -> Result<Vec<Vec<BindGroupLayoutEntry>>> {
+        let module = naga::front::wgsl::parse_str(wgsl_source).map_err(|error| {
+            anyhow!(
+                "Couldn't parse the shader:\n{}",
+                error.emit_to_string(wgsl_source)
+            )
+        })?;
+        let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .map_err(|error| {
+                anyhow!(
+                    "The shader is invalid:\n{}",
+                    error.emit_to_string(wgsl_source)
+                )
+            })?;
+
+        let mut groups: Vec<Vec<BindGroupLayoutEntry>> = Vec::new();
+        for (handle, global) in module.global_variables.iter() {
+            let Some(binding) = &global.binding else {
+                continue;
+            };
+            let name = global.name.as_deref().unwrap_or("<unnamed>");
+            let visibility = module
+                .entry_points
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !info.get_entry_point(*index)[handle].is_empty())
+                .fold(ShaderStages::NONE, |visibility, (_, entry_point)| {
+                    visibility | shader_stages(entry_point.stage)
+                });
+            let (ty, count) = binding_type(&module, global.space, global.ty)
+                .map_err(|error| anyhow!("Can't reflect the binding {name}: {error}"))?;
+
+            let group = binding.group as usize;
+            if groups.len() <= group {
+                groups.resize_with(group + 1, Vec::new);
+            }
+            groups[group].push(BindGroupLayoutEntry {
+                binding: binding.binding,
+                visibility,
+                ty,
+                count,
+            });
+        }
+        for entries in &mut groups {
+            entries.sort_by_key(|entry| entry.binding);
+        }
+        log::trace!(target: LOG_TARGET, "Reflected bind group layouts:\n{groups:#?}");
+        Ok(groups)
+    }
+}
+
+/// Returns the stages of a naga shader stage.
+fn shader_stages(stage: ShaderStage) -> ShaderStages {
+    match stage {
+        ShaderStage::Vertex => ShaderStages::VERTEX,
+        ShaderStage::Fragment => ShaderStages::FRAGMENT,
+        ShaderStage::Compute => ShaderStages::COMPUTE,
+        ShaderStage::Task => ShaderStages::TASK,
+        ShaderStage::Mesh => ShaderStages::MESH,
+    }
+}
+
+/// Returns the binding type of a global of type `ty` in `space`, and its count if it is a binding
+/// array.
+fn binding_type(
+    module: &Module,
+    space: AddressSpace,
+    ty: naga::Handle<naga::Type>,
+) -> Result<(BindingType, Option<NonZeroU32>)> {
+    let binding_type = match space {
+        AddressSpace::Uniform => BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: NonZeroU64::new(module.types[ty].inner.size(module.to_ctx()).into()),
+        },
+        AddressSpace::Storage { access } => BindingType::Buffer {
+            ty: BufferBindingType::Storage {
+                read_only: !access.contains(StorageAccess::STORE),
+            },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        AddressSpace::Handle => match module.types[ty].inner {
+            TypeInner::BindingArray { base, size } => {
+                let count = match size {
+                    ArraySize::Constant(count) => Some(count),
+                    ArraySize::Pending(_) | ArraySize::Dynamic => {
+                        bail!("binding arrays must have a constant size")
+                    }
+                };
+                return Ok((binding_type(module, space, base)?.0, count));
+            }
+            TypeInner::Sampler { comparison } => BindingType::Sampler(if comparison {
+                SamplerBindingType::Comparison
+            } else {
+                SamplerBindingType::Filtering
+            }),
+            TypeInner::Image {
+                dim,
+                arrayed,
+                class,
+            } => {
+                let view_dimension = match (dim, arrayed) {
+                    (ImageDimension::D1, _) => TextureViewDimension::D1,
+                    (ImageDimension::D2, false) => TextureViewDimension::D2,
+                    (ImageDimension::D2, true) => TextureViewDimension::D2Array,
+                    (ImageDimension::D3, _) => TextureViewDimension::D3,
+                    (ImageDimension::Cube, false) => TextureViewDimension::Cube,
+                    (ImageDimension::Cube, true) => TextureViewDimension::CubeArray,
+                };
+                match class {
+                    ImageClass::Sampled { kind, multi } => BindingType::Texture {
+                        sample_type: match kind {
+                            ScalarKind::Sint => TextureSampleType::Sint,
+                            ScalarKind::Uint => TextureSampleType::Uint,
+                            _ => TextureSampleType::Float { filterable: !multi },
+                        },
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    ImageClass::Depth { multi } => BindingType::Texture {
+                        sample_type: TextureSampleType::Depth,
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    ImageClass::Storage { format, access } => BindingType::StorageTexture {
+                        access: match (
+                            access.contains(StorageAccess::LOAD),
+                            access.contains(StorageAccess::STORE),
+                        ) {
+                            (true, true) => StorageTextureAccess::ReadWrite,
+                            (true, false) => StorageTextureAccess::ReadOnly,
+                            _ => StorageTextureAccess::WriteOnly,
+                        },
+                        format: texture_format(format),
+                        view_dimension,
+                    },
+                }
+            }
+            ref inner => bail!("{inner:?} can't be bound"),
+        },
+        space => bail!("globals in the {space:?} address space can't be bound"),
+    };
+    Ok((binding_type, None))
+}
+
+/// Returns the texture format of a naga storage format.
+fn texture_format(format: StorageFormat) -> TextureFormat {
+    match format {
+        StorageFormat::R8Unorm => TextureFormat::R8Unorm,
+        StorageFormat::R8Snorm => TextureFormat::R8Snorm,
+        StorageFormat::R8Uint => TextureFormat::R8Uint,
+        StorageFormat::R8Sint => TextureFormat::R8Sint,
+        StorageFormat::R16Uint => TextureFormat::R16Uint,
+        StorageFormat::R16Sint => TextureFormat::R16Sint,
+        StorageFormat::R16Float => TextureFormat::R16Float,
+        StorageFormat::Rg8Unorm => TextureFormat::Rg8Unorm,
+        StorageFormat::Rg8Snorm => TextureFormat::Rg8Snorm,
+        StorageFormat::Rg8Uint => TextureFormat::Rg8Uint,
+        StorageFormat::Rg8Sint => TextureFormat::Rg8Sint,
+        StorageFormat::R32Uint => TextureFormat::R32Uint,
+        StorageFormat::R32Sint => TextureFormat::R32Sint,
+        StorageFormat::R32Float => TextureFormat::R32Float,
+        StorageFormat::Rg16Uint => TextureFormat::Rg16Uint,
+        StorageFormat::Rg16Sint => TextureFormat::Rg16Sint,
+        StorageFormat::Rg16Float => TextureFormat::Rg16Float,
+        StorageFormat::Rgba8Unorm => TextureFormat::Rgba8Unorm,
+        StorageFormat::Rgba8Snorm => TextureFormat::Rgba8Snorm,
+        StorageFormat::Rgba8Uint => TextureFormat::Rgba8Uint,
+        StorageFormat::Rgba8Sint => TextureFormat::Rgba8Sint,
+        StorageFormat::Bgra8Unorm => TextureFormat::Bgra8Unorm,
+        StorageFormat::Rgb10a2Uint => TextureFormat::Rgb10a2Uint,
+        StorageFormat::Rgb10a2Unorm => TextureFormat::Rgb10a2Unorm,
+        StorageFormat::Rg11b10Ufloat => TextureFormat::Rg11b10Ufloat,
+        StorageFormat::R64Uint => TextureFormat::R64Uint,
+        StorageFormat::Rg32Uint => TextureFormat::Rg32Uint,
+        StorageFormat::Rg32Sint => TextureFormat::Rg32Sint,
+        StorageFormat::Rg32Float => TextureFormat::Rg32Float,
+        StorageFormat::Rgba16Uint => TextureFormat::Rgba16Uint,
+        StorageFormat::Rgba16Sint => TextureFormat::Rgba16Sint,
+        StorageFormat::Rgba16Float => TextureFormat::Rgba16Float,
+        StorageFormat::Rgba32Uint => TextureFormat::Rgba32Uint,
+        StorageFormat::Rgba32Sint => TextureFormat::Rgba32Sint,
+        StorageFormat::Rgba32Float => TextureFormat::Rgba32Float,
+        StorageFormat::R16Unorm => TextureFormat::R16Unorm,
+        StorageFormat::R16Snorm => TextureFormat::R16Snorm,
+        StorageFormat::Rg16Unorm => TextureFormat::Rg16Unorm,
+        StorageFormat::Rg16Snorm => TextureFormat::Rg16Snorm,
+        StorageFormat::Rgba16Unorm => TextureFormat::Rgba16Unorm,
+        StorageFormat::Rgba16Snorm => TextureFormat::Rgba16Snorm,
+    }
+}
@@ -0,0 +1,139 @@
+//! Helpers to create samplers.
+
+use wgpu::{AddressMode, Device, DownlevelFlags, FilterMode, Sampler, SamplerDescriptor};
+
+use crate::{GpuManager, LOG_TARGET};
+
+/// The highest anisotropy wgpu accepts. Backends clamp it further to what the hardware supports.
+const MAX_ANISOTROPY: u16 = 16;
+
+/// The samplers most applications need, created once by [`GpuManager::standard_samplers`].
+#[derive(Debug, Clone)]
+pub struct StandardSamplers {
+    /// Nearest filtering, clamping to the edges. Suits pixel art and reading texels exactly.
+    pub nearest_clamp: Sampler,
+    /// Linear filtering, clamping to the edges. Suits full-screen passes and UI.
+    pub linear_clamp: Sampler,
+    /// Linear filtering, repeating the texture. Suits tiled textures without mipmaps.
+    pub linear_repeat: Sampler,
+    /// Linear filtering between and within mip levels (trilinear), repeating the texture. Suits
+    /// mipmapped material textures.
+    pub linear_mipmap: Sampler,
+}
+
+impl StandardSamplers {
+    fn new(device: &Device) -> Self {
+        let sampler = |label, address_mode, filter, mipmap_filter| {
+            device.create_sampler(&SamplerDescriptor {
+                label: Some(label),
+                address_mode_u: address_mode,
+                address_mode_v: address_mode,
+                address_mode_w: address_mode,
+                mag_filter: filter,
+                min_filter: filter,
+                mipmap_filter,
+                ..Default::default()
+            })
+        };
+        Self {
+            nearest_clamp: sampler(
+                "Nearest clamp sampler",
+                AddressMode::ClampToEdge,
+                FilterMode::Nearest,
+                FilterMode::Nearest,
+            ),
+            linear_clamp: sampler(
+                "Linear clamp sampler",
+                AddressMode::ClampToEdge,
+                FilterMode::Linear,
+                FilterMode::Nearest,
+            ),
+            linear_repeat: sampler(
+                "Linear repeat sampler",
+                AddressMode::Repeat,
+                FilterMode::Linear,
+                FilterMode::Nearest,
+            ),
+            linear_mipmap: sampler(
+                "Linear mipmap sampler",
+                AddressMode::Repeat,
+                FilterMode::Linear,
+                FilterMode::Linear,
+            ),
+        }
+    }
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Returns the [`StandardSamplers`], creating them on the first call.
+    ///
+    /// The samplers are then shared by every caller instead of creating identical ones, until
+    /// [`GpuManager::switch_gpu`] recreates them for the new device. Clone a sampler to keep it beyond
+    /// the borrow of the manager, which is cheap.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let linear = manager.standard_samplers().linear_clamp.clone();
+    /// assert_eq!(linear, manager.standard_samplers().linear_clamp);
+    /// ```
+    pub fn standard_samplers(&self) -> &StandardSamplers {
+        self.standard_samplers.get_or_init(|| {
+            log::trace!(target: LOG_TARGET, "Creating standard samplers...");
+            StandardSamplers::new(&self.device)
+        })
+    }
+
+    /// Creates a sampler filtering textures anisotropically, with up to `max_anisotropy` samples.
+    ///
+    /// Anisotropic filtering requires linear filtering for magnification, minification and between
+    /// mip levels, which this sets, along with repeating address modes. The textures sampled with it
+    /// should have mipmaps to benefit from it.
+    ///
+    /// `max_anisotropy` is clamped between 1 and 16, with a warning when it is out of range. If the
+    /// adapter doesn't report support for anisotropic filtering, a warning is logged and a regular
+    /// trilinear sampler is created instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let sampler = manager.anisotropic_sampler(8);
+    /// ```
+    pub fn anisotropic_sampler(&self, max_anisotropy: u16) -> Sampler {
+        let mut anisotropy_clamp = max_anisotropy.clamp(1, MAX_ANISOTROPY);
+        if anisotropy_clamp != max_anisotropy {
+            log::warn!(
+                target: LOG_TARGET,
+                "Anisotropy must be between 1 and {MAX_ANISOTROPY}, clamping {max_anisotropy} to {anisotropy_clamp}."
+            );
+        }
+        let supported = self
+            .adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(DownlevelFlags::ANISOTROPIC_FILTERING);
+        if !supported && anisotropy_clamp > 1 {
+            log::warn!(
+                target: LOG_TARGET,
+                "The adapter doesn't support anisotropic filtering, using trilinear filtering instead."
+            );
+            anisotropy_clamp = 1;
+        }
+
+        self.device.create_sampler(&SamplerDescriptor {
+            label: Some("Anisotropic sampler"),
+            address_mode_u: AddressMode::Repeat,
+            address_mode_v: AddressMode::Repeat,
+            address_mode_w: AddressMode::Repeat,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Linear,
+            anisotropy_clamp,
+            ..Default::default()
+        })
+    }
+}
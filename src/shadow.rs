@@ -0,0 +1,120 @@
+//! Depth-only render passes and pipelines, as used for shadow mapping.
+
+use anyhow::{Result, bail};
+use wgpu::{
+    CommandEncoder, CompareFunction, DepthBiasState, DepthStencilState, MultisampleState,
+    PipelineLayout, PrimitiveState, RenderPass, RenderPipeline, RenderPipelineDescriptor,
+    StencilState, TextureFormat, TextureView, VertexState,
+};
+
+use crate::{GpuManager, LOG_TARGET, RenderPassBuilder};
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Begins a render pass without any color attachment, rendering to `depth_view` after clearing it
+    /// to `clear_depth`.
+    ///
+    /// The depth is stored at the end of the pass, so it can be sampled afterwards, and the stencil
+    /// aspect is left untouched. Use [`RenderPassBuilder`] for other operations.
+    ///
+    /// # Examples
+    /// Rendering to the depth texture of an [`OffscreenManager`](crate::OffscreenManager):
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::TextureFormat;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let manager = manager
+    ///     .into_offscreen(64, 64, TextureFormat::Rgba8Unorm)
+    ///     .unwrap()
+    ///     .with_depth(TextureFormat::Depth32Float)
+    ///     .unwrap();
+    ///
+    /// let module = manager.device().create_shader_module(wgpu::ShaderModuleDescriptor {
+    ///     label: None,
+    ///     source: wgpu::ShaderSource::Wgsl(
+    ///         "@vertex
+    ///         fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4f {
+    ///             let uv = vec2f(f32((index << 1) & 2), f32(index & 2));
+    ///             return vec4f(uv * 2.0 - 1.0, 0.5, 1.0);
+    ///         }"
+    ///         .into(),
+    ///     ),
+    /// });
+    /// let pipeline = manager
+    ///     .shadow_pipeline(
+    ///         None,
+    ///         wgpu::VertexState {
+    ///             module: &module,
+    ///             entry_point: None,
+    ///             compilation_options: Default::default(),
+    ///             buffers: &[],
+    ///         },
+    ///         TextureFormat::Depth32Float,
+    ///         Default::default(),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let depth_view = manager.surface_manager().depth_view().unwrap();
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// {
+    ///     let mut pass = manager.shadow_pass(&mut encoder, depth_view, 1.0);
+    ///     pass.set_pipeline(&pipeline);
+    ///     pass.draw(0..3, 0..1);
+    /// }
+    /// manager.queue().submit([encoder.finish()]);
+    /// ```
+    pub fn shadow_pass<'encoder>(
+        &self,
+        encoder: &'encoder mut CommandEncoder,
+        depth_view: &TextureView,
+        clear_depth: f32,
+    ) -> RenderPass<'encoder> {
+        RenderPassBuilder::new()
+            .label("Shadow pass")
+            .clear_depth(depth_view, clear_depth)
+            .begin(encoder)
+    }
+
+    /// Creates a render pipeline without a fragment stage or color target, writing the depth of what
+    /// `vertex` rasterizes to a `depth_format` texture, as used by [`GpuManager::shadow_pass`].
+    ///
+    /// Fragments closer or equal to the stored depth pass the depth test and overwrite it. `bias`
+    /// offsets the written depth, which avoids shadow acne when sampling the shadow map. Primitives
+    /// aren't culled, and the pipeline renders to single sampled textures.
+    ///
+    /// # Errors
+    /// This will error if `depth_format` has no depth aspect.
+    pub fn shadow_pipeline(
+        &self,
+        layout: Option<&PipelineLayout>,
+        vertex: VertexState<'_>,
+        depth_format: TextureFormat,
+        bias: DepthBiasState,
+    ) -> Result<RenderPipeline> {
+        if !depth_format.has_depth_aspect() {
+            bail!(
+                "{depth_format:?} has no depth aspect, so it can't be used for a shadow pipeline."
+            );
+        }
+        log::trace!(target: LOG_TARGET, "Creating shadow pipeline for {depth_format:?}...");
+        Ok(self
+            .device
+            .create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Shadow pipeline"),
+                layout,
+                vertex,
+                primitive: PrimitiveState::default(),
+                depth_stencil: Some(DepthStencilState {
+                    format: depth_format,
+                    depth_write_enabled: true,
+                    depth_compare: CompareFunction::LessEqual,
+                    stencil: StencilState::default(),
+                    bias,
+                }),
+                multisample: MultisampleState::default(),
+                fragment: None,
+                multiview: None,
+                cache: None,
+            }))
+    }
+}
@@ -0,0 +1,143 @@
+//! Waiting for queue submissions, with or without blocking the caller.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use anyhow::{Result, anyhow};
+use wgpu::{CommandBuffer, PollStatus, PollType, SubmissionIndex};
+
+use crate::{GpuManager, LOG_TARGET};
+
+/// The state shared between a [`Completed`] future and the callbacks completing it.
+#[derive(Debug)]
+pub(crate) struct Completion<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+impl<T> Default for Completion<T> {
+    fn default() -> Self {
+        Self {
+            result: None,
+            waker: None,
+        }
+    }
+}
+
+impl<T> Completion<T> {
+    pub(crate) fn complete(completion: &Mutex<Self>, result: T) {
+        let mut completion = completion
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // The work done callback and the polling thread may both complete it, the first one wins.
+        if completion.result.is_none() {
+            completion.result = Some(result);
+            if let Some(waker) = completion.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Resolves once its [`Completion`] is completed, for example when the GPU has finished a submission.
+pub(crate) struct Completed<T> {
+    pub(crate) completion: Arc<Mutex<Completion<T>>>,
+}
+
+impl<T> Future for Completed<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut completion = self
+            .completion
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match completion.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                completion.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Submits `command_buffers` and returns their [`SubmissionIndex`], along with a future resolving
+    /// once the GPU has finished them.
+    ///
+    /// Unlike polling with [`PollType::Wait`], awaiting the future doesn't block the executor, which
+    /// makes it suitable for async servers doing GPU compute. Completion is detected through
+    /// [`Queue::on_submitted_work_done`](wgpu::Queue::on_submitted_work_done), whose callback only runs
+    /// while the [`Device`](wgpu::Device) is polled, so a thread is spawned to poll it until the
+    /// submission is done.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let encoder = manager.device().create_command_encoder(&Default::default());
+    /// let (_submission, done) = manager.submit_and_wait_async([encoder.finish()]);
+    /// pollster::block_on(done).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// The future resolves to an error if polling the [`Device`](wgpu::Device) fails.
+    #[track_caller]
+    pub fn submit_and_wait_async(
+        &self,
+        command_buffers: impl IntoIterator<Item = CommandBuffer>,
+    ) -> (
+        SubmissionIndex,
+        impl Future<Output = Result<()>> + Send + 'static,
+    ) {
+        let submission = self.validate("Submitting", || self.queue.submit(command_buffers));
+        let completion = Arc::new(Mutex::new(Completion::<Result<()>>::default()));
+
+        let on_done = completion.clone();
+        self.queue.on_submitted_work_done(move || {
+            Completion::complete(&on_done, Ok(()));
+        });
+
+        let device = self.device.clone();
+        let index = submission.clone();
+        let on_error = completion.clone();
+        std::thread::spawn(move || {
+            log::trace!(target: LOG_TARGET, "Polling the device until the submission is done...");
+            if let Err(error) = device.poll(PollType::WaitForSubmissionIndex(index)) {
+                Completion::complete(
+                    &on_error,
+                    Err(anyhow!("Couldn't wait for the submission: {error}.")),
+                );
+            }
+        });
+
+        (submission, Completed { completion })
+    }
+
+    /// Blocks until the GPU has finished the submission `index`, and returns the status of the
+    /// [`Device`](wgpu::Device) poll.
+    ///
+    /// Unlike polling with [`PollType::Wait`], this doesn't wait for submissions made after `index`.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let encoder = manager.device().create_command_encoder(&Default::default());
+    /// let submission = manager.queue().submit([encoder.finish()]);
+    /// manager.wait_for(submission).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if polling the [`Device`](wgpu::Device) fails, for example if `index` belongs to
+    /// another device or its wait timed out.
+    pub fn wait_for(&self, index: SubmissionIndex) -> Result<PollStatus> {
+        log::trace!(target: LOG_TARGET, "Waiting for submission {index:?}...");
+        Ok(self.device.poll(PollType::WaitForSubmissionIndex(index))?)
+    }
+}
@@ -0,0 +1,744 @@
+//! Helpers to choose texture formats, move texture data between the CPU and the GPU, and render to
+//! single subresources of textures.
+
+use anyhow::{Context, Result, bail};
+use wgpu::{
+    BufferDescriptor, BufferUsages, COPY_BYTES_PER_ROW_ALIGNMENT, Color, CommandEncoder,
+    CommandEncoderDescriptor, DownlevelFlags, Extent3d, Features, ImageSubresourceRange, LoadOp,
+    MapMode, PollType, RenderPass, TexelCopyBufferInfo, TexelCopyBufferLayout,
+    TexelCopyTextureInfo, Texture, TextureAspect, TextureDimension, TextureFormat, TextureUsages,
+    TextureView, TextureViewDescriptor, TextureViewDimension,
+};
+
+use crate::{GpuManager, LOG_TARGET, RenderPassBuilder};
+
+/// Returns the size of a tightly packed row of `width` pixels of `bytes_per_pixel` bytes each.
+///
+/// # Examples
+/// ```
+/// assert_eq!(gpu_manager::unpadded_bytes_per_row(0, 4), 0);
+/// assert_eq!(gpu_manager::unpadded_bytes_per_row(3, 4), 12);
+/// ```
+pub fn unpadded_bytes_per_row(width: u32, bytes_per_pixel: u32) -> u32 {
+    width * bytes_per_pixel
+}
+
+/// Returns the size of a row of `width` pixels of `bytes_per_pixel` bytes each, padded to
+/// [`COPY_BYTES_PER_ROW_ALIGNMENT`] as required when copying between a texture and a buffer.
+///
+/// # Examples
+/// ```
+/// use gpu_manager::padded_bytes_per_row;
+///
+/// assert_eq!(padded_bytes_per_row(0, 4), 0);
+/// assert_eq!(padded_bytes_per_row(1, 4), 256);
+/// assert_eq!(padded_bytes_per_row(64, 4), 256);
+/// assert_eq!(padded_bytes_per_row(65, 4), 512);
+/// assert_eq!(padded_bytes_per_row(100, 3), 512);
+/// ```
+pub fn padded_bytes_per_row(width: u32, bytes_per_pixel: u32) -> u32 {
+    unpadded_bytes_per_row(width, bytes_per_pixel).next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT)
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Writes `data` to the start of `texture`'s first mip level, covering `size` texels.
+    ///
+    /// `data` may either be tightly packed (`size.width * bytes_per_pixel` bytes per row), or have each
+    /// row padded to [`COPY_BYTES_PER_ROW_ALIGNMENT`], as is the case for data read back from a buffer.
+    /// The layout is deduced from the length of `data`.
+    ///
+    /// # Examples
+    /// Uploading a 3x3 RGBA image and reading it back:
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let size = wgpu::Extent3d {
+    ///     width: 3,
+    ///     height: 3,
+    ///     depth_or_array_layers: 1,
+    /// };
+    /// let texture = manager.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size,
+    ///     mip_level_count: 1,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: wgpu::TextureFormat::Rgba8Unorm,
+    ///     usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+    ///     view_formats: &[],
+    /// });
+    /// let pixels: Vec<u8> = (0..3 * 3 * 4).collect();
+    /// manager.write_texture(&texture, &pixels, size, 4).unwrap();
+    ///
+    /// // Rows copied to a buffer must be padded.
+    /// let padded_row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    /// let buffer = manager.device().create_buffer(&wgpu::BufferDescriptor {
+    ///     label: None,
+    ///     size: (padded_row * 3) as u64,
+    ///     usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+    ///     mapped_at_creation: false,
+    /// });
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// encoder.copy_texture_to_buffer(
+    ///     texture.as_image_copy(),
+    ///     wgpu::TexelCopyBufferInfo {
+    ///         buffer: &buffer,
+    ///         layout: wgpu::TexelCopyBufferLayout {
+    ///             offset: 0,
+    ///             bytes_per_row: Some(padded_row),
+    ///             rows_per_image: None,
+    ///         },
+    ///     },
+    ///     size,
+    /// );
+    /// manager.queue().submit([encoder.finish()]);
+    ///
+    /// buffer.slice(..).map_async(wgpu::MapMode::Read, |result| result.unwrap());
+    /// manager.device().poll(wgpu::PollType::Wait).unwrap();
+    /// let mapped = buffer.slice(..).get_mapped_range();
+    /// for row in 0..3 {
+    ///     let start = row * padded_row as usize;
+    ///     assert_eq!(&mapped[start..start + 12], &pixels[row * 12..(row + 1) * 12]);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if the length of `data` matches neither the tightly packed nor the padded layout.
+    pub fn write_texture(
+        &self,
+        texture: &Texture,
+        data: &[u8],
+        size: Extent3d,
+        bytes_per_pixel: u32,
+    ) -> Result<()> {
+        let rows = u64::from(size.height) * u64::from(size.depth_or_array_layers);
+        let unpadded_bytes_per_row = unpadded_bytes_per_row(size.width, bytes_per_pixel);
+        let padded_bytes_per_row = padded_bytes_per_row(size.width, bytes_per_pixel);
+
+        let bytes_per_row = match data.len() as u64 {
+            len if len == u64::from(unpadded_bytes_per_row) * rows => unpadded_bytes_per_row,
+            len if len == u64::from(padded_bytes_per_row) * rows => padded_bytes_per_row,
+            len => bail!(
+                "Texture data is {len} bytes long, but a {}x{}x{} region with {bytes_per_pixel} bytes per pixel \
+                needs {} bytes, or {} with padded rows.",
+                size.width,
+                size.height,
+                size.depth_or_array_layers,
+                u64::from(unpadded_bytes_per_row) * rows,
+                u64::from(padded_bytes_per_row) * rows,
+            ),
+        };
+
+        self.queue.write_texture(
+            texture.as_image_copy(),
+            data,
+            TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(size.height),
+            },
+            size,
+        );
+        Ok(())
+    }
+
+    /// Writes each of `layers` to the array layer of the same index of `texture`'s first mip level,
+    /// covering `layer_size` texels from its origin.
+    ///
+    /// Like with [`GpuManager::write_texture`], each layer may either be tightly packed or have its rows
+    /// padded to [`COPY_BYTES_PER_ROW_ALIGNMENT`]. The layers are gathered into a single staging buffer
+    /// and copied to `texture` in one submission, which needs [`TextureUsages::COPY_DST`].
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let texture = manager.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size: wgpu::Extent3d {
+    ///         width: 2,
+    ///         height: 2,
+    ///         depth_or_array_layers: 3,
+    ///     },
+    ///     mip_level_count: 1,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: wgpu::TextureFormat::Rgba8Unorm,
+    ///     usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+    ///     view_formats: &[],
+    /// });
+    /// let layers: Vec<Vec<u8>> = (0..3).map(|layer| vec![layer; 2 * 2 * 4]).collect();
+    /// let layers: Vec<&[u8]> = layers.iter().map(Vec::as_slice).collect();
+    /// let layer_size = wgpu::Extent3d {
+    ///     width: 2,
+    ///     height: 2,
+    ///     depth_or_array_layers: 1,
+    /// };
+    /// manager.upload_layers(&texture, &layers, layer_size).unwrap();
+    ///
+    /// assert_eq!(manager.read_texture(&texture).unwrap(), layers.concat());
+    /// ```
+    ///
+    /// Depth textures can't be uploaded to:
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let size = wgpu::Extent3d {
+    ///     width: 2,
+    ///     height: 2,
+    ///     depth_or_array_layers: 1,
+    /// };
+    /// let depth = manager.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size,
+    ///     mip_level_count: 1,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: wgpu::TextureFormat::Depth32Float,
+    ///     usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+    ///         | wgpu::TextureUsages::COPY_DST
+    ///         | wgpu::TextureUsages::COPY_SRC,
+    ///     view_formats: &[],
+    /// });
+    /// assert!(manager.upload_layers(&depth, &[&[0; 2 * 2 * 4]], size).is_err());
+    /// assert!(manager.read_texture(&depth).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) `texture` wasn't created with [`TextureUsages::COPY_DST`], 2) it uses a
+    /// compressed, depth or stencil format, 3) there are more layers than `texture` has array layers,
+    /// 4) `layer_size` doesn't fit in `texture`, or 5) the length of a layer matches neither the
+    /// tightly packed nor the padded layout.
+    #[track_caller]
+    pub fn upload_layers(
+        &self,
+        texture: &Texture,
+        layers: &[&[u8]],
+        layer_size: Extent3d,
+    ) -> Result<()> {
+        if !texture.usage().contains(TextureUsages::COPY_DST) {
+            bail!("Only textures with the COPY_DST usage can be uploaded to.");
+        }
+        let format = texture.format();
+        if format.is_depth_stencil_format() {
+            bail!("Can't upload layers to textures in the {format:?} depth or stencil format.");
+        }
+        let Some(bytes_per_pixel) = format
+            .block_copy_size(None)
+            .filter(|_| format.block_dimensions() == (1, 1))
+        else {
+            bail!("Can't upload layers to textures in the {format:?} format.");
+        };
+        let size = texture.size();
+        if layers.len() > size.depth_or_array_layers as usize {
+            bail!(
+                "Can't upload {} layers to a texture with {} array layers.",
+                layers.len(),
+                size.depth_or_array_layers
+            );
+        }
+        if layer_size.width > size.width
+            || layer_size.height > size.height
+            || layer_size.depth_or_array_layers != 1
+        {
+            bail!(
+                "Layers of {}x{}x{} texels don't fit in the {}x{} layers of the texture.",
+                layer_size.width,
+                layer_size.height,
+                layer_size.depth_or_array_layers,
+                size.width,
+                size.height
+            );
+        }
+        if layers.is_empty() || layer_size.width == 0 || layer_size.height == 0 {
+            return Ok(());
+        }
+
+        let unpadded_bytes_per_row = unpadded_bytes_per_row(layer_size.width, bytes_per_pixel);
+        let padded_bytes_per_row = padded_bytes_per_row(layer_size.width, bytes_per_pixel);
+        let unpadded_len = unpadded_bytes_per_row as usize * layer_size.height as usize;
+        let padded_len = padded_bytes_per_row as usize * layer_size.height as usize;
+
+        let mut layer_bytes_per_row = Vec::with_capacity(layers.len());
+        for (index, layer) in layers.iter().enumerate() {
+            layer_bytes_per_row.push(match layer.len() {
+                len if len == unpadded_len => unpadded_bytes_per_row,
+                len if len == padded_len => padded_bytes_per_row,
+                len => bail!(
+                    "Layer {index} is {len} bytes long, but a {}x{} layer with {bytes_per_pixel} bytes per pixel \
+                    needs {unpadded_len} bytes, or {padded_len} with padded rows.",
+                    layer_size.width,
+                    layer_size.height,
+                ),
+            });
+        }
+
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("Layer upload buffer"),
+            size: (padded_len * layers.len()) as u64,
+            usage: BufferUsages::COPY_SRC,
+            mapped_at_creation: true,
+        });
+        let mut mapped = buffer.slice(..).get_mapped_range_mut();
+        for ((layer, bytes_per_row), staging) in layers
+            .iter()
+            .zip(layer_bytes_per_row)
+            .zip(mapped.chunks_exact_mut(padded_len))
+        {
+            for (row, staging_row) in layer
+                .chunks_exact(bytes_per_row as usize)
+                .zip(staging.chunks_exact_mut(padded_bytes_per_row as usize))
+            {
+                staging_row[..unpadded_bytes_per_row as usize]
+                    .copy_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        drop(mapped);
+        buffer.unmap();
+
+        log::trace!(target: LOG_TARGET, "Uploading {} texture layers...", layers.len());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Layer upload encoder"),
+            });
+        encoder.copy_buffer_to_texture(
+            TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(layer_size.height),
+                },
+            },
+            texture.as_image_copy(),
+            Extent3d {
+                depth_or_array_layers: layers.len() as u32,
+                ..layer_size
+            },
+        );
+        self.validate("Submitting the layer upload", || {
+            self.queue.submit([encoder.finish()])
+        });
+        Ok(())
+    }
+
+    /// Clears every mip level and layer of `texture` to zero, outside of a render pass.
+    ///
+    /// This is handy to initialize storage textures, which can't be render attachments. wgpu can only
+    /// clear to zero this way: to clear to another color, use a render pass (see
+    /// [`RenderPassBuilder::clear_color`](crate::RenderPassBuilder::clear_color)). The texture needs
+    /// [`TextureUsages::COPY_DST`].
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::builder().clear_texture(true).build()).unwrap();
+    /// let size = wgpu::Extent3d {
+    ///     width: 3,
+    ///     height: 3,
+    ///     depth_or_array_layers: 1,
+    /// };
+    /// let texture = manager.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size,
+    ///     mip_level_count: 1,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: wgpu::TextureFormat::Rgba8Unorm,
+    ///     usage: wgpu::TextureUsages::STORAGE_BINDING
+    ///         | wgpu::TextureUsages::COPY_DST
+    ///         | wgpu::TextureUsages::COPY_SRC,
+    ///     view_formats: &[],
+    /// });
+    /// manager.write_texture(&texture, &[255; 3 * 3 * 4], size, 4).unwrap();
+    /// manager.clear_texture(&texture).unwrap();
+    /// assert!(manager.read_texture(&texture).unwrap().iter().all(|byte| *byte == 0));
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) [`Features::CLEAR_TEXTURE`] wasn't requested through
+    /// [`GpuManagerBuilder::clear_texture`](crate::GpuManagerBuilder::clear_texture), or 2) `texture`
+    /// lacks the [`TextureUsages::COPY_DST`] usage.
+    #[track_caller]
+    pub fn clear_texture(&self, texture: &Texture) -> Result<()> {
+        if !self.device.features().contains(Features::CLEAR_TEXTURE) {
+            bail!(
+                "Clearing textures requires the CLEAR_TEXTURE feature, enable it with `GpuManagerBuilder::clear_texture`."
+            );
+        }
+        if !texture.usage().contains(TextureUsages::COPY_DST) {
+            bail!("Only textures with the COPY_DST usage can be cleared.");
+        }
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Clear encoder"),
+            });
+        encoder.clear_texture(texture, &ImageSubresourceRange::default());
+        self.validate("Submitting the texture clear", || {
+            self.queue.submit([encoder.finish()])
+        });
+        Ok(())
+    }
+
+    /// Creates a 2D view of a single mip level and array layer of `texture`, such as a face of a cubemap
+    /// or a level of a mip chain, to render to or sample from on its own.
+    ///
+    /// Depth textures, such as cubemap shadow maps, can be rendered to by passing the view to
+    /// [`GpuManager::shadow_pass`]. For color textures, [`GpuManager::subresource_pass`] also begins the
+    /// render pass.
+    ///
+    /// # Errors
+    /// This will error if 1) `texture` isn't a 2D texture, 2) it has no mip level at `mip_level`, or 3)
+    /// it has no array layer at `array_layer`.
+    pub fn subresource_view(
+        &self,
+        texture: &Texture,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Result<TextureView> {
+        if texture.dimension() != TextureDimension::D2 {
+            bail!(
+                "Subresource views can only be created for 2D textures, not {:?} ones. Render to a slice of a 3D \
+                texture through `RenderPassColorAttachment::depth_slice` instead.",
+                texture.dimension()
+            );
+        }
+        if mip_level >= texture.mip_level_count() {
+            bail!(
+                "The texture has no mip level {mip_level}, it only has {}.",
+                texture.mip_level_count()
+            );
+        }
+        if array_layer >= texture.depth_or_array_layers() {
+            bail!(
+                "The texture has no array layer {array_layer}, it only has {}.",
+                texture.depth_or_array_layers()
+            );
+        }
+        Ok(texture.create_view(&TextureViewDescriptor {
+            label: Some("Subresource view"),
+            dimension: Some(TextureViewDimension::D2),
+            base_mip_level: mip_level,
+            mip_level_count: Some(1),
+            base_array_layer: array_layer,
+            array_layer_count: Some(1),
+            ..Default::default()
+        }))
+    }
+
+    /// Begins a render pass on `encoder` rendering to a single mip level and array layer of `texture`,
+    /// through a [`GpuManager::subresource_view`] of it.
+    ///
+    /// `load` either clears the subresource to a color or keeps its previous contents. The render target
+    /// is `texture`'s [`Texture::width`] and [`Texture::height`] halved for each mip level, so the
+    /// viewport shrinks with deeper levels. Environment maps can be generated by rendering each face of a
+    /// cubemap, that is each of its 6 array layers, with a matching camera.
+    ///
+    /// # Examples
+    /// Clearing a face of the second mip level of a cubemap:
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let cubemap = manager.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size: wgpu::Extent3d {
+    ///         width: 16,
+    ///         height: 16,
+    ///         depth_or_array_layers: 6,
+    ///     },
+    ///     mip_level_count: 2,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: wgpu::TextureFormat::Rgba8Unorm,
+    ///     usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+    ///     view_formats: &[],
+    /// });
+    ///
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// manager
+    ///     .subresource_pass(&mut encoder, &cubemap, 1, 4, wgpu::LoadOp::Clear(wgpu::Color::RED))
+    ///     .unwrap();
+    /// manager.queue().submit([encoder.finish()]);
+    ///
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// assert!(
+    ///     manager
+    ///         .subresource_pass(&mut encoder, &cubemap, 2, 0, wgpu::LoadOp::Load)
+    ///         .is_err()
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) the view can't be created, see [`GpuManager::subresource_view`], 2)
+    /// `texture` lacks the [`TextureUsages::RENDER_ATTACHMENT`] usage, or 3) it has a depth or stencil
+    /// format.
+    pub fn subresource_pass<'encoder>(
+        &self,
+        encoder: &'encoder mut CommandEncoder,
+        texture: &Texture,
+        mip_level: u32,
+        array_layer: u32,
+        load: LoadOp<Color>,
+    ) -> Result<RenderPass<'encoder>> {
+        if !texture.usage().contains(TextureUsages::RENDER_ATTACHMENT) {
+            bail!("Only textures with the RENDER_ATTACHMENT usage can be rendered to.");
+        }
+        if texture.format().is_depth_stencil_format() {
+            bail!(
+                "Can't render to the {:?} texture as a color target. Pass a `GpuManager::subresource_view` of it \
+                to `GpuManager::shadow_pass` instead.",
+                texture.format()
+            );
+        }
+        let view = self.subresource_view(texture, mip_level, array_layer)?;
+        log::trace!(
+            target: LOG_TARGET,
+            "Beginning render pass to mip level {mip_level} of array layer {array_layer}..."
+        );
+        let builder = RenderPassBuilder::new().label("Subresource pass");
+        let builder = match load {
+            LoadOp::Clear(color) => builder.clear_color(&view, color),
+            LoadOp::Load => builder.load_color(&view),
+        };
+        Ok(builder.begin(encoder))
+    }
+
+    /// Reads back the first mip level of `texture`, returning its texels with tightly packed rows.
+    ///
+    /// The texture needs [`TextureUsages::COPY_SRC`](wgpu::TextureUsages::COPY_SRC). This blocks until
+    /// the GPU has finished all the work submitted so far.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let (texture, _) = manager
+    ///     .create_output_texture(3, 3, wgpu::TextureFormat::Rgba8Unorm)
+    ///     .unwrap();
+    /// let pixels = manager.read_texture(&texture).unwrap();
+    /// assert_eq!(pixels.len(), 3 * 3 * 4);
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) `texture` uses a compressed, depth or stencil format, 2) mapping the
+    /// readback buffer fails or 3) polling the [`Device`](wgpu::Device) fails.
+    #[track_caller]
+    pub fn read_texture(&self, texture: &Texture) -> Result<Vec<u8>> {
+        let format = texture.format();
+        if format.is_depth_stencil_format() {
+            bail!(
+                "Can't read back textures in the {format:?} depth or stencil format, use `GpuManager::read_depth` instead."
+            );
+        }
+        let Some(bytes_per_pixel) = format
+            .block_copy_size(None)
+            .filter(|_| format.block_dimensions() == (1, 1))
+        else {
+            bail!("Can't read back textures in the {format:?} format.");
+        };
+        self.read_back(texture, TextureAspect::All, bytes_per_pixel)
+    }
+
+    /// Reads back the depth of the first mip level of `texture`, such as the depth texture of an
+    /// [`OffscreenManager`](crate::OffscreenManager), to visualize it while debugging.
+    ///
+    /// Depths are returned row by row, as values between 0 and 1. The texture needs
+    /// [`TextureUsages::COPY_SRC`], and can't be multisampled. This blocks until the GPU has finished all
+    /// the work submitted so far.
+    ///
+    /// Only the depth aspect of [`TextureFormat::Depth32Float`], [`TextureFormat::Depth32FloatStencil8`]
+    /// and [`TextureFormat::Depth16Unorm`] can be copied to a buffer. [`TextureFormat::Depth24Plus`] and
+    /// [`TextureFormat::Depth24PlusStencil8`] have a layout that depends on the backend, so they can't:
+    /// sample them in a shader that writes the depth to an `R32Float` texture instead, and read that one
+    /// back with [`GpuManager::read_texture`]. The same goes for every depth format on adapters without
+    /// [`DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES`], such as OpenGL ES ones.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::TextureFormat;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let depth = manager.device().create_texture(&wgpu::TextureDescriptor {
+    ///     label: None,
+    ///     size: wgpu::Extent3d {
+    ///         width: 4,
+    ///         height: 4,
+    ///         depth_or_array_layers: 1,
+    ///     },
+    ///     mip_level_count: 1,
+    ///     sample_count: 1,
+    ///     dimension: wgpu::TextureDimension::D2,
+    ///     format: TextureFormat::Depth32Float,
+    ///     usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+    ///     view_formats: &[],
+    /// });
+    /// let view = depth.create_view(&Default::default());
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// manager.shadow_pass(&mut encoder, &view, 0.25);
+    /// manager.queue().submit([encoder.finish()]);
+    ///
+    /// match manager.read_depth(&depth) {
+    ///     Ok(depths) => assert_eq!(depths, [0.25; 4 * 4]),
+    ///     // OpenGL ES (and WebGL) can't copy depth textures.
+    ///     Err(error) => assert_eq!(manager.adapter_info().backend, wgpu::Backend::Gl, "{error}"),
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// This will error if 1) the depth of `texture` can't be copied, either because of its format, its
+    /// usages, the adapter's capabilities or because it is multisampled, 2) mapping the readback buffer fails or 3) polling the
+    /// [`Device`](wgpu::Device) fails.
+    #[track_caller]
+    pub fn read_depth(&self, texture: &Texture) -> Result<Vec<f32>> {
+        let format = texture.format();
+        if !format.has_depth_aspect() {
+            bail!("The {format:?} format has no depth to read back.");
+        }
+        let Some(bytes_per_pixel) = format.block_copy_size(Some(TextureAspect::DepthOnly)) else {
+            bail!(
+                "The depth of {format:?} textures can't be copied. Write it to an R32Float texture in a shader \
+                and read that one back instead."
+            );
+        };
+        if !self
+            .adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES)
+        {
+            bail!(
+                "The adapter can't copy depth textures. Write the depth to an R32Float texture in a shader \
+                and read that one back instead."
+            );
+        }
+        if !texture.usage().contains(TextureUsages::COPY_SRC) {
+            bail!("Only textures with the COPY_SRC usage can be read back.");
+        }
+        if texture.sample_count() > 1 {
+            bail!("Multisampled depth textures can't be read back, resolve them first.");
+        }
+
+        let data = self.read_back(texture, TextureAspect::DepthOnly, bytes_per_pixel)?;
+        Ok(match format {
+            TextureFormat::Depth16Unorm => data
+                .chunks_exact(2)
+                .map(|bytes| {
+                    f32::from(u16::from_ne_bytes([bytes[0], bytes[1]])) / f32::from(u16::MAX)
+                })
+                .collect(),
+            _ => data
+                .chunks_exact(4)
+                .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+        })
+    }
+
+    /// Copies `aspect` of the first mip level of `texture` to a buffer, and returns its texels of
+    /// `bytes_per_pixel` bytes with tightly packed rows.
+    #[track_caller]
+    fn read_back(
+        &self,
+        texture: &Texture,
+        aspect: TextureAspect,
+        bytes_per_pixel: u32,
+    ) -> Result<Vec<u8>> {
+        let size = texture.size();
+        let unpadded_bytes_per_row = unpadded_bytes_per_row(size.width, bytes_per_pixel);
+        let padded_bytes_per_row = padded_bytes_per_row(size.width, bytes_per_pixel);
+        let rows = u64::from(size.height) * u64::from(size.depth_or_array_layers);
+
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("Readback buffer"),
+            size: u64::from(padded_bytes_per_row) * rows,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Readback encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            TexelCopyTextureInfo {
+                aspect,
+                ..texture.as_image_copy()
+            },
+            TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(size.height),
+                },
+            },
+            size,
+        );
+        let submission = self.validate("Submitting the readback", || {
+            self.queue.submit([encoder.finish()])
+        });
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device
+            .poll(PollType::WaitForSubmissionIndex(submission))?;
+        receiver
+            .recv()
+            .context("The readback buffer was never mapped.")??;
+
+        let mapped = slice.get_mapped_range();
+        let mut data = Vec::with_capacity((u64::from(unpadded_bytes_per_row) * rows) as usize);
+        for row in mapped.chunks_exact(padded_bytes_per_row as usize) {
+            data.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+        drop(mapped);
+        buffer.unmap();
+        Ok(data)
+    }
+
+    /// Returns the first format of `prefer` that can be used as a depth attachment on the contained
+    /// [`Device`](wgpu::Device), or [`TextureFormat::Depth32Float`] if none can.
+    ///
+    /// Formats that aren't depth formats, that need features the device doesn't have (such as
+    /// [`TextureFormat::Depth32FloatStencil8`]) or that it can't render to are skipped. The chosen
+    /// format is logged.
+    ///
+    /// # Examples
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::TextureFormat;
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let format =
+    ///     manager.best_depth_format(&[TextureFormat::Rgba8Unorm, TextureFormat::Depth24PlusStencil8]);
+    /// assert_eq!(format, TextureFormat::Depth24PlusStencil8);
+    /// ```
+    pub fn best_depth_format(&self, prefer: &[TextureFormat]) -> TextureFormat {
+        let device_features = self.device.features();
+        let format = prefer
+            .iter()
+            .copied()
+            .find(|format| {
+                format.has_depth_aspect()
+                    && device_features.contains(format.required_features())
+                    && self
+                        .format_features(*format)
+                        .allowed_usages
+                        .contains(TextureUsages::RENDER_ATTACHMENT)
+            })
+            .unwrap_or(TextureFormat::Depth32Float);
+        log::debug!(target: LOG_TARGET, "Using depth format {format:?}.");
+        format
+    }
+}
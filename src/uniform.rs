@@ -0,0 +1,88 @@
+//! Creating a uniform buffer along with its bind group in one call.
+//!
+//! Only available when the `bytemuck` feature is activated.
+
+use std::num::NonZeroU64;
+
+use bytemuck::Pod;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsages, ShaderStages,
+};
+
+use crate::GpuManager;
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Creates a uniform buffer holding `data`, a [`BindGroupLayout`] with a single uniform buffer
+    /// entry at binding 0, and a [`BindGroup`] binding the buffer to it.
+    ///
+    /// The entry is visible to the vertex, fragment and compute stages, and requires the whole of `T`
+    /// to be bound. `T` must match the layout of the uniform in the shader, including its padding: for
+    /// example, a `vec3f` takes 16 bytes. The buffer also has the [`BufferUsages::COPY_DST`] and
+    /// [`BufferUsages::COPY_SRC`] usages, so it can be updated through
+    /// [`Queue::write_buffer`](wgpu::Queue::write_buffer) without recreating the bind group, and read
+    /// back.
+    ///
+    /// # Examples
+    /// Creating the bind group, and reading its buffer back:
+    /// ```
+    /// use gpu_manager::GpuManager;
+    /// use wgpu::{BufferUsages, MapMode, PollType};
+    ///
+    /// let manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// let color = [1.0f32, 0.5, 0.25, 1.0];
+    /// let (buffer, _bind_group, _layout) = manager.uniform_bind_group(&color);
+    ///
+    /// let readback = manager.device().create_buffer(&wgpu::BufferDescriptor {
+    ///     label: None,
+    ///     size: buffer.size(),
+    ///     usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+    ///     mapped_at_creation: false,
+    /// });
+    /// let mut encoder = manager.device().create_command_encoder(&Default::default());
+    /// encoder.copy_buffer_to_buffer(&buffer, 0, &readback, 0, buffer.size());
+    /// manager.queue().submit([encoder.finish()]);
+    ///
+    /// readback.slice(..).map_async(MapMode::Read, |result| result.unwrap());
+    /// manager.device().poll(PollType::Wait).unwrap();
+    /// let bytes: Vec<u8> = color.iter().flat_map(|value| value.to_ne_bytes()).collect();
+    /// assert_eq!(&readback.slice(..).get_mapped_range()[..], &bytes[..]);
+    /// ```
+    ///
+    /// # Panics
+    /// This panics if `T` is zero sized, since empty buffers can't be bound.
+    pub fn uniform_bind_group<T: Pod>(&self, data: &T) -> (Buffer, BindGroup, BindGroupLayout) {
+        let size = NonZeroU64::new(size_of::<T>() as u64)
+            .expect("zero sized types can't be bound as uniform buffers");
+        let buffer = self.device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Uniform buffer"),
+            contents: bytemuck::bytes_of(data),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+        });
+        let layout = self
+            .device
+            .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("Uniform layout"),
+                entries: &[BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX_FRAGMENT | ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: Some(size),
+                    },
+                    count: None,
+                }],
+            });
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Uniform bind group"),
+            layout: &layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+        (buffer, bind_group, layout)
+    }
+}
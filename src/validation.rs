@@ -0,0 +1,138 @@
+//! A strict validation mode for development, checking the work recorded and submitted through the
+//! manager.
+
+use std::panic::Location;
+use std::sync::{Condvar, Mutex};
+use std::thread::ThreadId;
+
+use wgpu::ErrorFilter;
+
+use crate::executor::block_on;
+use crate::{GpuManager, LOG_TARGET};
+
+/// Serializes the error scopes the manager pushes. wgpu keeps a single scope stack per device, shared
+/// by every thread, so scopes pushed from two threads at once would catch each other's errors.
+///
+/// The lock is reentrant, since scopes nest on a single thread, for example a strictly validated
+/// submission inside [`GpuManager::capture_errors`].
+#[derive(Debug, Default)]
+pub(crate) struct ErrorScopeLock {
+    /// The thread holding the lock, and how many times it took it.
+    owner: Mutex<Option<(ThreadId, usize)>>,
+    released: Condvar,
+}
+
+/// Releases an [`ErrorScopeLock`] when dropped.
+pub(crate) struct ErrorScopeGuard<'lock>(&'lock ErrorScopeLock);
+
+impl ErrorScopeLock {
+    /// Blocks until no other thread has error scopes pushed through the manager.
+    pub(crate) fn lock(&self) -> ErrorScopeGuard<'_> {
+        let thread = std::thread::current().id();
+        let mut owner = self
+            .owner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        loop {
+            match &mut *owner {
+                Some((holder, depth)) if *holder == thread => {
+                    *depth += 1;
+                    break;
+                }
+                Some(_) => {
+                    owner = self
+                        .released
+                        .wait(owner)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                }
+                None => {
+                    *owner = Some((thread, 1));
+                    break;
+                }
+            }
+        }
+        ErrorScopeGuard(self)
+    }
+}
+
+impl Drop for ErrorScopeGuard<'_> {
+    fn drop(&mut self) {
+        let mut owner = self
+            .0
+            .owner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((_, depth)) = &mut *owner {
+            *depth -= 1;
+            if *depth == 0 {
+                *owner = None;
+                self.0.released.notify_one();
+            }
+        }
+    }
+}
+
+impl<SurfaceManager> GpuManager<SurfaceManager> {
+    /// Sets whether the work recorded and submitted through the manager is checked for validation
+    /// errors, panicking on the first one. Defaults to `false`.
+    ///
+    /// When enabled, finishing encoders ([`GpuManager::release_encoder`]) and submitting work
+    /// ([`GpuManager::submit_encoders`], [`GpuManager::submit_and_wait_async`]) are wrapped in a
+    /// validation [error scope](wgpu::Device::push_error_scope), as are the encoders the manager records
+    /// and submits itself: the frames of `GpuManager::render`, and the work of its texture, animation
+    /// and profiling helpers. A captured error is logged, then panics with the location of the call to
+    /// the manager that caused it, which is more precise than the uncaptured error handler. Work
+    /// recorded or submitted directly through [`GpuManager::device`] or [`GpuManager::queue`] isn't
+    /// checked; wrap it in [`GpuManager::capture_errors`] instead.
+    ///
+    /// This is meant for development, for example enabled with `cfg!(debug_assertions)`. Each check
+    /// waits for the error scope to be resolved, which is immediate on native backends. When disabled,
+    /// no scope is pushed and the only overhead is checking the flag.
+    ///
+    /// # Examples
+    /// ```should_panic
+    /// use gpu_manager::GpuManager;
+    ///
+    /// let mut manager = pollster::block_on(GpuManager::simple()).unwrap();
+    /// manager.set_strict_validation(true);
+    ///
+    /// let buffer = manager.device().create_buffer(&wgpu::BufferDescriptor {
+    ///     label: None,
+    ///     size: 4,
+    ///     usage: wgpu::BufferUsages::COPY_SRC,
+    ///     mapped_at_creation: false,
+    /// });
+    /// let mut encoder = manager.acquire_encoder(None);
+    /// // The buffer isn't a copy destination.
+    /// encoder.copy_buffer_to_buffer(&buffer, 0, &buffer, 0, 4);
+    /// manager.release_encoder(encoder);
+    /// ```
+    pub fn set_strict_validation(&mut self, enabled: bool) {
+        log::debug!(target: LOG_TARGET, "Setting strict validation to {enabled}.");
+        self.strict_validation = enabled;
+    }
+
+    /// Returns whether strict validation is enabled, see [`GpuManager::set_strict_validation`].
+    pub fn strict_validation(&self) -> bool {
+        self.strict_validation
+    }
+
+    /// Runs `f`, panicking if strict validation is enabled and it raised a validation error.
+    #[track_caller]
+    pub(crate) fn validate<T>(&self, operation: &str, f: impl FnOnce() -> T) -> T {
+        if !self.strict_validation {
+            return f();
+        }
+        let guard = self.error_scopes.lock();
+        self.device.push_error_scope(ErrorFilter::Validation);
+        let output = f();
+        let error = block_on(self.device.pop_error_scope());
+        drop(guard);
+        if let Some(error) = error {
+            let location = Location::caller();
+            log::error!(target: LOG_TARGET, "{operation} at {location} failed validation: {error}");
+            panic!("{operation} at {location} failed validation: {error}");
+        }
+        output
+    }
+}